@@ -0,0 +1,318 @@
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Trimmed output of `ghostty +show-config --default --docs`, used when no
+/// ghostty binary is available. Regenerate it from a current release with
+/// `ghostty +show-config --default --docs > ghostty_options.txt`.
+const BUNDLED_SNAPSHOT: &str = include_str!("ghostty_options.txt");
+
+/// Where the ghostty binary lives when it is not on PATH.
+pub(crate) const GHOSTTY_BINARIES: [&str; 2] = ["ghostty", "/Applications/Ghostty.app/Contents/MacOS/ghostty"];
+
+/// Keys that may appear more than once even if the docs don't say so.
+const REPEATABLE: [&str; 7] = ["keybind", "palette", "font-feature", "font-family", "config-file", "custom-shader", "font-variation"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OptionKind {
+    Bool,
+    Number,
+    Color,
+    /// One of `values`, or a comma-separated list of them.
+    Enum,
+    String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptionDoc {
+    pub name: String,
+    pub kind: OptionKind,
+    /// Default values; repeatable options may list several.
+    pub defaults: Vec<String>,
+    pub repeatable: bool,
+    /// Allowed values taken from the docs' bullet list.
+    pub values: Vec<String>,
+    pub docs: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptionCatalog {
+    /// "installed" when read from the ghostty binary, "bundled" otherwise.
+    pub source: String,
+    pub options: Vec<OptionDoc>,
+}
+
+/// Parse `ghostty +show-config --default --docs` output: `#` doc comments
+/// followed by one or more `key = value` lines.
+pub fn parse(text: &str) -> Vec<OptionDoc> {
+    let mut options: Vec<OptionDoc> = Vec::new();
+    let mut doc: Vec<String> = Vec::new();
+    let mut last_was_key = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(comment) = trimmed.strip_prefix('#') {
+            if last_was_key {
+                doc.clear();
+                last_was_key = false;
+            }
+            doc.push(comment.strip_prefix(' ').unwrap_or(comment).to_string());
+            continue;
+        }
+        if trimmed.is_empty() {
+            if last_was_key {
+                doc.clear();
+                last_was_key = false;
+            }
+            continue;
+        }
+        let Some((key, value)) = trimmed.split_once('=') else { continue };
+        let (key, value) = (key.trim(), value.trim());
+        last_was_key = true;
+
+        if let Some(existing) = options.iter_mut().find(|o| o.name == key) {
+            existing.repeatable = true;
+            if !value.is_empty() {
+                existing.defaults.push(value.to_string());
+            }
+            continue;
+        }
+
+        let docs = doc.join("\n").trim().to_string();
+        let values = enum_values(&docs);
+        let lower = docs.to_lowercase();
+        options.push(OptionDoc {
+            name: key.to_string(),
+            // Inferred once every default line has been read
+            kind: OptionKind::String,
+            defaults: if value.is_empty() { vec![] } else { vec![value.to_string()] },
+            repeatable: REPEATABLE.contains(&key) || lower.contains("repeat") || lower.contains("multiple times"),
+            values,
+            docs,
+        });
+    }
+    for option in &mut options {
+        option.kind = infer_kind(&option.defaults, &option.values);
+    }
+    options
+}
+
+/// Phrases that introduce the list of an option's allowed values.
+const VALUES_INTROS: [&str; 4] = ["valid values", "available values", "possible values", "allowable values"];
+
+/// The "* `value`" bullets of an explicit list of allowed values, e.g.
+/// after "Valid values:". Other bullet lists, such as the keybind prefixes,
+/// are examples and not values.
+fn enum_values(docs: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut in_list = false;
+    for line in docs.lines() {
+        let trimmed = line.trim();
+        let lower = trimmed.to_lowercase();
+        if lower.ends_with(':') && VALUES_INTROS.iter().any(|intro| lower.contains(intro)) {
+            in_list = true;
+        } else if !in_list || trimmed.is_empty() {
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix("* `") {
+            values.extend(rest.split('`').next().map(str::to_string));
+        } else if !line.starts_with(' ') {
+            // A paragraph after the list; indented lines continue a bullet
+            in_list = false;
+        }
+    }
+    values
+}
+
+/// The kind every default agrees on. An enum whose defaults are not all
+/// among its values is treated as a string, since the list is incomplete.
+fn infer_kind(defaults: &[String], values: &[String]) -> OptionKind {
+    let all = |f: fn(&str) -> bool| !defaults.is_empty() && defaults.iter().all(|d| f(d));
+    if !values.is_empty() {
+        let listed = defaults.iter().flat_map(|d| d.split(',')).all(|v| values.iter().any(|allowed| allowed == v.trim()));
+        if listed { OptionKind::Enum } else { OptionKind::String }
+    } else if all(|d| d == "true" || d == "false") {
+        OptionKind::Bool
+    } else if all(|d| d.parse::<f64>().is_ok()) {
+        OptionKind::Number
+    } else if all(|d| d.starts_with('#')) {
+        OptionKind::Color
+    } else {
+        OptionKind::String
+    }
+}
+
+fn from_binary() -> Option<String> {
+    GHOSTTY_BINARIES.iter().find_map(|bin| {
+        let output = Command::new(bin).args(["+show-config", "--default", "--docs"]).output().ok()?;
+        let text = String::from_utf8_lossy(&output.stdout).to_string();
+        (output.status.success() && !text.trim().is_empty()).then_some(text)
+    })
+}
+
+/// The option catalog, read from the installed ghostty once per process.
+pub fn catalog() -> &'static OptionCatalog {
+    static CATALOG: OnceLock<OptionCatalog> = OnceLock::new();
+    CATALOG.get_or_init(|| match from_binary() {
+        Some(text) => OptionCatalog { source: "installed".into(), options: parse(&text) },
+        None => OptionCatalog { source: "bundled".into(), options: parse(BUNDLED_SNAPSHOT) },
+    })
+}
+
+impl OptionCatalog {
+    pub fn get(&self, name: &str) -> Option<&OptionDoc> {
+        self.options.iter().find(|o| o.name == name)
+    }
+
+    /// Options whose name or docs contain every word of `query`, name matches first.
+    pub fn search(&self, query: &str) -> Vec<OptionDoc> {
+        let words: Vec<String> = query.split_whitespace().map(|w| w.to_lowercase()).collect();
+        let mut hits: Vec<(bool, &OptionDoc)> = self
+            .options
+            .iter()
+            .filter(|o| {
+                let haystack = format!("{} {}", o.name, o.docs).to_lowercase();
+                words.iter().all(|w| haystack.contains(w.as_str()))
+            })
+            .map(|o| (words.iter().all(|w| o.name.contains(w.as_str())), o))
+            .collect();
+        hits.sort_by_key(|(name_match, _)| !name_match);
+        hits.into_iter().map(|(_, o)| o.clone()).collect()
+    }
+
+    pub fn validate(&self, key: &str, value: &str) -> Result<(), String> {
+        let Some(option) = self.get(key) else {
+            return Err(format!("Unknown Ghostty option '{}'", key));
+        };
+        if value.is_empty() {
+            return Ok(());
+        }
+        let valid = match option.kind {
+            // Several boolean options also take a number (e.g. background-blur)
+            OptionKind::Bool => value == "true" || value == "false" || value.parse::<f64>().is_ok(),
+            OptionKind::Number => value.split(',').all(|v| v.trim().parse::<f64>().is_ok()),
+            OptionKind::Color => is_color(value),
+            OptionKind::Enum => value.split(',').all(|v| option.values.iter().any(|allowed| allowed == v.trim())),
+            OptionKind::String => true,
+        };
+        if valid {
+            Ok(())
+        } else if option.kind == OptionKind::Enum {
+            Err(format!("Invalid value '{}' for {} (expected {})", value, key, option.values.join(", ")))
+        } else {
+            Err(format!("Invalid value '{}' for {}", value, key))
+        }
+    }
+
+    /// Check every `key = value` line of a rendered Ghostty config.
+    pub fn validate_config_text(&self, text: &str) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let mut seen: Vec<&str> = Vec::new();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                warnings.push(format!("Ghostty config line is not 'key = value': {}", line));
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+            if let Err(e) = self.validate(key, value) {
+                warnings.push(e);
+            } else if seen.contains(&key) && !self.get(key).is_some_and(|o| o.repeatable) {
+                warnings.push(format!("Ghostty option '{}' is set more than once; the last value wins", key));
+            }
+            seen.push(key);
+        }
+        warnings
+    }
+}
+
+fn is_color(value: &str) -> bool {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    (hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+        // X11 color names such as "black" or "light gray"
+        || value.chars().all(|c| c.is_ascii_alphabetic() || c == ' ')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundled() -> OptionCatalog {
+        OptionCatalog { source: "bundled".into(), options: parse(BUNDLED_SNAPSHOT) }
+    }
+
+    #[test]
+    fn keybind_is_a_repeatable_string() {
+        let catalog = bundled();
+        let keybind = catalog.get("keybind").unwrap();
+        assert_eq!(keybind.kind, OptionKind::String);
+        assert!(keybind.repeatable);
+        assert!(keybind.values.is_empty());
+        assert!(keybind.defaults.len() > 1);
+        assert!(catalog.validate_config_text("keybind = global:cmd+grave_accent=toggle_quick_terminal").is_empty());
+        assert!(catalog.validate_config_text("keybind = ctrl+a=ignore\nkeybind = ctrl+b=unbind").is_empty());
+    }
+
+    #[test]
+    fn only_listed_values_make_an_enum() {
+        let catalog = bundled();
+        let cursor = catalog.get("cursor-style").unwrap();
+        assert_eq!(cursor.kind, OptionKind::Enum);
+        assert!(cursor.values.contains(&"block".to_string()));
+        assert!(catalog.validate("cursor-style", "triangle").is_err());
+
+        let palette = catalog.get("palette").unwrap();
+        assert_eq!(palette.kind, OptionKind::String);
+        assert!(palette.repeatable && palette.defaults.len() == 16);
+    }
+
+    #[test]
+    fn bullets_outside_a_values_list_are_not_values() {
+        let text = "# Some examples:\n#   * `all:` applies everywhere\n# Valid values:\n#   * `on`\n#   * `off`\n#     (the default)\n# More text.\n#   * `later`\nmode = on\n";
+        assert_eq!(parse(text)[0].values, vec!["on", "off"]);
+    }
+
+    #[test]
+    fn every_default_decides_the_kind() {
+        let text = "# Valid values:\n#   * `a`\n#   * `b`\nletter = c\n\n# Number or bool.\nmixed = true\nmixed = 4\n\n# Sizes.\nsize = 1\nsize = 2.5\n";
+        let options = parse(text);
+        let kind = |name: &str| options.iter().find(|o| o.name == name).unwrap().kind;
+        // c is not listed, so the list is incomplete
+        assert_eq!(kind("letter"), OptionKind::String);
+        assert_eq!(kind("mixed"), OptionKind::String);
+        assert_eq!(kind("size"), OptionKind::Number);
+    }
+
+    #[test]
+    fn config_text_problems_are_reported_per_line() {
+        let catalog = bundled();
+        let text = "# comment\nfont-size = 14\nfont-size = 15\ncursor-style = triangle\nno-such-option = 1\nnot a setting\nbackground = #0b0e14\n";
+        assert_eq!(
+            catalog.validate_config_text(text),
+            [
+                "Ghostty option 'font-size' is set more than once; the last value wins",
+                "Invalid value 'triangle' for cursor-style (expected block, bar, underline, block_hollow)",
+                "Unknown Ghostty option 'no-such-option'",
+                "Ghostty config line is not 'key = value': not a setting",
+            ]
+        );
+        assert!(catalog.validate("background", "#0b0e1").is_err());
+    }
+
+    #[test]
+    fn the_default_config_is_valid_ghostty() {
+        let config = crate::config::schema::GhosttyConfig::default();
+        let rendered = crate::generator::engine::render_apply_template(&config, "apply/ghostty_config.tera").unwrap();
+        assert_eq!(bundled().validate_config_text(&rendered), Vec::<String>::new());
+    }
+
+    #[test]
+    fn search_lists_name_matches_first() {
+        let hits = bundled().search("cursor style");
+        assert_eq!(hits[0].name, "cursor-style");
+        assert!(hits.iter().all(|o| format!("{} {}", o.name, o.docs).to_lowercase().contains("cursor")));
+        assert!(bundled().search("no such words anywhere").is_empty());
+    }
+}
@@ -1,10 +1,11 @@
-use super::palette::AyuPalette;
+use super::palette::{default_palette_name, AyuPalette};
 use super::sections::*;
 use super::schema::GhosttyConfig;
 
 pub fn default_config() -> GhosttyConfig {
     GhosttyConfig {
         palette: AyuPalette::default(),
+        palette_name: default_palette_name(),
         clean_artifacts: default_clean_artifacts(),
         packages: default_packages(),
         ghostty: default_ghostty(),
@@ -18,6 +19,7 @@ pub fn default_config() -> GhosttyConfig {
         themes: default_themes(),
         tmux_scripts: default_tmux_scripts(),
         zshrc: default_zshrc(),
+        custom_sections: Default::default(),
     }
 }
 
@@ -140,6 +142,7 @@ fn default_ghostty() -> GhosttyTermConfig {
         custom_shader_enabled: true,
         custom_shader_path: "~/.config/ghostty/shaders/vignette-bloom.glsl".into(),
         install_community_shaders: true,
+        shaders: vec![],
         // Keybinds
         keybinds: vec![
             kb("shift+page_up", "scroll_page_up"),
@@ -165,6 +168,8 @@ fn default_ghostty() -> GhosttyTermConfig {
         quick_terminal_size: "".into(),
         quick_terminal_animation_duration: 0.0,
         quick_terminal_autohide: true,
+        extra_options: vec![],
+        layout: GhosttyLayout::Single,
     }
 }
 
@@ -456,6 +461,7 @@ fn default_tool_configs() -> ToolConfigsConfig {
 fn default_git() -> GitConfig {
     GitConfig {
         enabled: true,
+        apply_mode: GitApplyMode::OnlyIfUnset,
         delta: DeltaConfig {
             navigate: true,
             dark: true,
@@ -477,6 +483,8 @@ fn default_git() -> GitConfig {
             wrap_max_lines: 2,
             true_color: "auto".into(),
             hyperlinks: false,
+            from_palette: true,
+            overrides: DeltaStyleOverrides::default(),
         },
         merge_conflictstyle: "diff3".into(),
         diff_algorithm: "histogram".into(),
@@ -501,6 +509,8 @@ fn default_git() -> GitConfig {
             galias("wip", "!git add -A && git commit -m \"wip\""),
             galias("cleanup", "!git branch --merged | grep -v \"\\\\*\\\\|main\\\\|master\" | xargs -n 1 git branch -d"),
         ],
+        identities: vec![],
+        signing: GitSigningConfig::default(),
     }
 }
 
@@ -0,0 +1,568 @@
+use super::catalog::GHOSTTY_BINARIES;
+use super::sections::KeyBind;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::process::Command;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Modifier {
+    Shift,
+    Ctrl,
+    Alt,
+    Super,
+}
+
+impl Modifier {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "shift" => Some(Self::Shift),
+            "ctrl" | "control" => Some(Self::Ctrl),
+            "alt" | "opt" | "option" => Some(Self::Alt),
+            "super" | "cmd" | "command" => Some(Self::Super),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Shift => "shift",
+            Self::Ctrl => "ctrl",
+            Self::Alt => "alt",
+            Self::Super => "super",
+        }
+    }
+}
+
+/// One key press of a trigger, e.g. `ctrl+shift+t`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyPress {
+    /// Sorted and deduplicated, so equal chords compare equal.
+    pub mods: Vec<Modifier>,
+    pub key: String,
+    /// A physical key (W3C code such as `KeyA`, or `physical:` prefixed)
+    /// rather than the character the layout produces.
+    pub physical: bool,
+}
+
+impl fmt::Display for KeyPress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for m in &self.mods {
+            write!(f, "{}+", m.as_str())?;
+        }
+        if self.physical && !is_w3c_code(&self.key) {
+            write!(f, "physical:")?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trigger {
+    /// `global:` — fires even when Ghostty is not focused (macOS).
+    pub global: bool,
+    /// `all:` — applies to every terminal surface, not just the focused one.
+    pub all: bool,
+    /// `unconsumed:` — the key is still sent to the program.
+    pub unconsumed: bool,
+    /// `performable:` — only consumed if the action can be performed.
+    pub performable: bool,
+    /// Key presses separated by `>`; more than one makes a leader sequence.
+    pub sequence: Vec<KeyPress>,
+}
+
+impl Trigger {
+    pub fn is_sequence(&self) -> bool {
+        self.sequence.len() > 1
+    }
+
+    /// The trigger without prefixes, used to compare bindings.
+    pub fn chord(&self) -> String {
+        self.sequence.iter().map(|k| k.to_string()).collect::<Vec<_>>().join(">")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Action {
+    pub name: String,
+    pub param: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParsedKeyBind {
+    pub trigger: Trigger,
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticLevel {
+    Error,
+    Warning,
+}
+
+/// A problem with one keybind entry; `index` points into `ghostty.keybinds`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeybindDiagnostic {
+    pub index: usize,
+    pub key: String,
+    pub action: String,
+    pub level: DiagnosticLevel,
+    pub message: String,
+}
+
+impl fmt::Display for KeybindDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Keybind {}={}: {}", self.key, self.action, self.message)
+    }
+}
+
+// ── Keys ────────────────────────────────────────────────────────────
+
+const NAMED_KEYS: &[&str] = &[
+    "space", "enter", "tab", "backspace", "escape", "insert", "delete", "home", "end",
+    "page_up", "page_down", "up", "down", "left", "right", "caps_lock", "scroll_lock",
+    "num_lock", "print_screen", "pause", "context_menu", "grave_accent", "minus", "equal",
+    "bracket_left", "bracket_right", "backslash", "semicolon", "apostrophe", "comma",
+    "period", "slash", "plus", "kp_0", "kp_1", "kp_2", "kp_3", "kp_4", "kp_5", "kp_6",
+    "kp_7", "kp_8", "kp_9", "kp_decimal", "kp_divide", "kp_multiply", "kp_subtract",
+    "kp_add", "kp_enter", "kp_equal", "kp_separator", "kp_left", "kp_right", "kp_up",
+    "kp_down", "kp_page_up", "kp_page_down", "kp_home", "kp_end", "kp_insert", "kp_delete",
+];
+
+const W3C_CODES: &[&str] = &[
+    "Backquote", "Backslash", "BracketLeft", "BracketRight", "Comma", "Equal", "Minus",
+    "Period", "Quote", "Semicolon", "Slash", "Space", "Enter", "Tab", "Backspace", "Escape",
+    "Insert", "Delete", "Home", "End", "PageUp", "PageDown", "ArrowUp", "ArrowDown",
+    "ArrowLeft", "ArrowRight", "CapsLock", "IntlBackslash", "ContextMenu",
+];
+
+fn is_function_key(key: &str) -> bool {
+    key.strip_prefix('f')
+        .or_else(|| key.strip_prefix('F'))
+        .and_then(|n| n.parse::<u8>().ok())
+        .is_some_and(|n| (1..=25).contains(&n))
+}
+
+fn is_w3c_code(key: &str) -> bool {
+    let single = |rest: Option<&str>, ok: fn(char) -> bool| {
+        rest.is_some_and(|r| r.chars().count() == 1 && r.chars().all(ok))
+    };
+    W3C_CODES.contains(&key)
+        || single(key.strip_prefix("Key"), |c| c.is_ascii_uppercase())
+        || single(key.strip_prefix("Digit"), |c| c.is_ascii_digit())
+        || key.strip_prefix("Numpad").is_some_and(|r| !r.is_empty())
+        || (key.starts_with('F') && is_function_key(key))
+}
+
+fn parse_key_press(text: &str) -> Result<KeyPress, String> {
+    if text.is_empty() {
+        return Err("empty key in trigger".into());
+    }
+    // `+` itself is a valid key, so split from the right and keep a trailing "+"
+    let (mods_text, key) = match text.strip_suffix("++") {
+        Some(rest) => (rest, "+"),
+        None if text == "+" => ("", "+"),
+        None => match text.rsplit_once('+') {
+            Some((mods, key)) => (mods, key),
+            None => ("", text),
+        },
+    };
+    if key.is_empty() {
+        return Err(format!("'{}' has no key after the modifiers", text));
+    }
+
+    let mut mods = Vec::new();
+    for name in mods_text.split('+').filter(|m| !m.is_empty()) {
+        let modifier = Modifier::parse(&name.to_lowercase())
+            .ok_or_else(|| format!("unknown modifier '{}'", name))?;
+        if mods.contains(&modifier) {
+            return Err(format!("modifier '{}' is repeated", name));
+        }
+        mods.push(modifier);
+    }
+    mods.sort();
+
+    let (key, physical) = match key.strip_prefix("physical:") {
+        Some(k) => (k, true),
+        None => (key, is_w3c_code(key)),
+    };
+    let known = key.chars().count() == 1
+        || NAMED_KEYS.contains(&key)
+        || is_function_key(key)
+        || (physical && is_w3c_code(key));
+    if !known {
+        return Err(format!("unknown key '{}'", key));
+    }
+    // Letters are case-insensitive; shift is expressed as a modifier
+    let key = if key.chars().count() == 1 { key.to_lowercase() } else { key.to_string() };
+    Ok(KeyPress { mods, key, physical })
+}
+
+pub fn parse_trigger(text: &str) -> Result<Trigger, String> {
+    let mut trigger = Trigger::default();
+    let mut rest = text.trim();
+    loop {
+        if let Some(r) = rest.strip_prefix("global:") {
+            trigger.global = true;
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix("all:") {
+            trigger.all = true;
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix("unconsumed:") {
+            trigger.unconsumed = true;
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix("performable:") {
+            trigger.performable = true;
+            rest = r;
+        } else {
+            break;
+        }
+    }
+    if rest.is_empty() {
+        return Err("trigger has no key".into());
+    }
+    // `>` is also a key, so only split on separators that sit between presses
+    let mut presses = Vec::new();
+    let mut current = String::new();
+    for c in rest.chars() {
+        if c == '>' && !current.is_empty() && !current.ends_with('+') {
+            presses.push(std::mem::take(&mut current));
+        } else {
+            current.push(c);
+        }
+    }
+    presses.push(current);
+    trigger.sequence = presses.iter().map(|p| parse_key_press(p)).collect::<Result<_, _>>()?;
+    if trigger.global && trigger.is_sequence() {
+        return Err("global: triggers cannot be sequences".into());
+    }
+    Ok(trigger)
+}
+
+// ── Actions ─────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy)]
+enum Param {
+    None,
+    Int,
+    Float,
+    Text,
+    OneOf(&'static [&'static str]),
+}
+
+const SPLIT_DIRECTIONS: &[&str] = &["right", "down", "left", "up", "auto"];
+const SPLIT_TARGETS: &[&str] = &["previous", "next", "up", "left", "down", "right"];
+const SELECTIONS: &[&str] = &["left", "right", "up", "down", "page_up", "page_down", "home", "end", "beginning_of_line", "end_of_line"];
+const FILE_ACTIONS: &[&str] = &["copy", "paste", "open"];
+const INSPECTOR: &[&str] = &["toggle", "show", "hide"];
+
+/// Actions with their parameter; `(name, param, required)`. Ghostty adds
+/// actions over time, so names missing here are checked against
+/// `ghostty +list-actions` instead of being rejected.
+const ACTIONS: &[(&str, Param, bool)] = &[
+    ("ignore", Param::None, false),
+    ("unbind", Param::None, false),
+    ("csi", Param::Text, true),
+    ("esc", Param::Text, true),
+    ("text", Param::Text, true),
+    ("cursor_key", Param::Text, true),
+    ("reset", Param::None, false),
+    ("copy_to_clipboard", Param::None, false),
+    ("paste_from_clipboard", Param::None, false),
+    ("paste_from_selection", Param::None, false),
+    ("copy_url_to_clipboard", Param::None, false),
+    ("increase_font_size", Param::Float, false),
+    ("decrease_font_size", Param::Float, false),
+    ("reset_font_size", Param::None, false),
+    ("set_font_size", Param::Float, true),
+    ("clear_screen", Param::None, false),
+    ("select_all", Param::None, false),
+    ("scroll_to_top", Param::None, false),
+    ("scroll_to_bottom", Param::None, false),
+    ("scroll_to_selection", Param::None, false),
+    ("scroll_page_up", Param::None, false),
+    ("scroll_page_down", Param::None, false),
+    ("scroll_page_fractional", Param::Float, true),
+    ("scroll_page_lines", Param::Int, true),
+    ("adjust_selection", Param::OneOf(SELECTIONS), true),
+    ("jump_to_prompt", Param::Int, true),
+    ("write_scrollback_file", Param::OneOf(FILE_ACTIONS), true),
+    ("write_screen_file", Param::OneOf(FILE_ACTIONS), true),
+    ("write_selection_file", Param::OneOf(FILE_ACTIONS), true),
+    ("new_window", Param::None, false),
+    ("new_tab", Param::None, false),
+    ("previous_tab", Param::None, false),
+    ("next_tab", Param::None, false),
+    ("last_tab", Param::None, false),
+    ("goto_tab", Param::Int, true),
+    ("move_tab", Param::Int, true),
+    ("toggle_tab_overview", Param::None, false),
+    ("prompt_surface_title", Param::None, false),
+    ("new_split", Param::OneOf(SPLIT_DIRECTIONS), true),
+    ("goto_split", Param::OneOf(SPLIT_TARGETS), true),
+    ("toggle_split_zoom", Param::None, false),
+    ("resize_split", Param::Text, true),
+    ("equalize_splits", Param::None, false),
+    ("inspector", Param::OneOf(INSPECTOR), true),
+    ("toggle_command_palette", Param::None, false),
+    ("open_config", Param::None, false),
+    ("reload_config", Param::None, false),
+    ("close_surface", Param::None, false),
+    ("close_tab", Param::None, false),
+    ("close_window", Param::None, false),
+    ("close_all_windows", Param::None, false),
+    ("toggle_maximize", Param::None, false),
+    ("toggle_fullscreen", Param::None, false),
+    ("toggle_window_decorations", Param::None, false),
+    ("toggle_window_float_on_top", Param::None, false),
+    ("toggle_secure_input", Param::None, false),
+    ("toggle_quick_terminal", Param::None, false),
+    ("toggle_visibility", Param::None, false),
+    ("check_for_updates", Param::None, false),
+    ("undo", Param::None, false),
+    ("redo", Param::None, false),
+    ("quit", Param::None, false),
+];
+
+pub fn parse_action(text: &str) -> Result<Action, String> {
+    let text = text.trim();
+    let (name, param) = match text.split_once(':') {
+        Some((name, param)) => (name, Some(param.to_string())),
+        None => (text, None),
+    };
+    let Some((_, kind, required)) = ACTIONS.iter().find(|(n, _, _)| *n == name) else {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+            return Err(format!("'{}' is not an action name", name));
+        }
+        // Reported by `check` when the installed ghostty does not know it either
+        return Ok(Action { name: name.to_string(), param });
+    };
+    match (&param, kind) {
+        (None, _) if *required => return Err(format!("action '{}' needs a parameter", name)),
+        (None, _) => {}
+        (Some(_), Param::None) => return Err(format!("action '{}' takes no parameter", name)),
+        (Some(p), Param::Int) if p.parse::<i64>().is_err() => {
+            return Err(format!("action '{}' expects an integer, got '{}'", name, p));
+        }
+        (Some(p), Param::Float) if p.parse::<f64>().is_err() => {
+            return Err(format!("action '{}' expects a number, got '{}'", name, p));
+        }
+        (Some(p), Param::OneOf(values)) if !values.contains(&p.as_str()) => {
+            return Err(format!("action '{}' expects one of {}, got '{}'", name, values.join(", "), p));
+        }
+        (Some(p), Param::Text) if p.is_empty() => {
+            return Err(format!("action '{}' has an empty parameter", name));
+        }
+        _ => {}
+    }
+    Ok(Action { name: name.to_string(), param })
+}
+
+/// Action names listed by the installed ghostty, read once per process.
+fn installed_actions() -> Option<&'static [String]> {
+    static INSTALLED: OnceLock<Option<Vec<String>>> = OnceLock::new();
+    INSTALLED
+        .get_or_init(|| {
+            GHOSTTY_BINARIES.iter().find_map(|bin| {
+                let output = Command::new(bin).arg("+list-actions").output().ok()?;
+                let names: Vec<String> = String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .filter(|l| !l.starts_with(char::is_whitespace))
+                    .filter_map(|l| l.split(':').next())
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty())
+                    .collect();
+                (output.status.success() && !names.is_empty()).then_some(names)
+            })
+        })
+        .as_deref()
+}
+
+/// How to report an action missing from `ACTIONS`: an error when the
+/// installed ghostty does not list it, a warning when there is no ghostty
+/// to ask.
+fn unknown_action(name: &str, installed: Option<&[String]>) -> Option<(DiagnosticLevel, String)> {
+    match installed {
+        Some(names) if names.iter().any(|n| n == name) => None,
+        Some(_) => Some((DiagnosticLevel::Error, format!("unknown action '{}'", name))),
+        None => Some((
+            DiagnosticLevel::Warning,
+            format!("unknown action '{}'; it may need a newer Ghostty than this app knows about", name),
+        )),
+    }
+}
+
+impl KeyBind {
+    pub fn parse(&self) -> Result<ParsedKeyBind, String> {
+        Ok(ParsedKeyBind { trigger: parse_trigger(&self.key)?, action: parse_action(&self.action)? })
+    }
+}
+
+// ── Diagnostics ─────────────────────────────────────────────────────
+
+/// Parse every binding and report invalid entries, bindings overridden by a
+/// later one with the same trigger, and single keys that collide with the
+/// first press of a sequence. Ghostty keeps whichever was defined last.
+pub fn check(keybinds: &[KeyBind]) -> Vec<KeybindDiagnostic> {
+    let mut diagnostics = Vec::new();
+    let diagnostic = |index: usize, level: DiagnosticLevel, message: String| KeybindDiagnostic {
+        index,
+        key: keybinds[index].key.clone(),
+        action: keybinds[index].action.clone(),
+        level,
+        message,
+    };
+
+    let mut parsed: Vec<(usize, ParsedKeyBind)> = Vec::new();
+    for (index, kb) in keybinds.iter().enumerate() {
+        match kb.parse() {
+            Ok(p) => {
+                let tabled = ACTIONS.iter().any(|(name, _, _)| *name == p.action.name);
+                if let Some((level, message)) = (!tabled).then(|| unknown_action(&p.action.name, installed_actions())).flatten() {
+                    diagnostics.push(diagnostic(index, level, message));
+                }
+                parsed.push((index, p));
+            }
+            Err(e) => diagnostics.push(diagnostic(index, DiagnosticLevel::Error, e)),
+        }
+    }
+
+    for (i, (index, bind)) in parsed.iter().enumerate() {
+        let chord = bind.trigger.chord();
+        for (later_index, later) in &parsed[i + 1..] {
+            let later_chord = later.trigger.chord();
+            if later_chord == chord {
+                let message = if later.action.name == "unbind" {
+                    format!("removed by the unbind in entry {}", later_index + 1)
+                } else {
+                    format!("overridden by entry {} ({}={})", later_index + 1, keybinds[*later_index].key, keybinds[*later_index].action)
+                };
+                diagnostics.push(diagnostic(*index, DiagnosticLevel::Warning, message));
+            } else if is_prefix(&bind.trigger, &later.trigger) || is_prefix(&later.trigger, &bind.trigger) {
+                diagnostics.push(diagnostic(
+                    *index,
+                    DiagnosticLevel::Warning,
+                    format!(
+                        "'{}' and '{}' (entry {}) share a leader key; only entry {} will work",
+                        chord,
+                        later_chord,
+                        later_index + 1,
+                        later_index + 1
+                    ),
+                ));
+            }
+        }
+    }
+    diagnostics.sort_by_key(|d| d.index);
+    diagnostics
+}
+
+/// `a` is a strict prefix of `b`, e.g. `ctrl+a` and `ctrl+a>n`.
+fn is_prefix(a: &Trigger, b: &Trigger) -> bool {
+    a.sequence.len() < b.sequence.len() && b.sequence.starts_with(&a.sequence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bind(key: &str, action: &str) -> KeyBind {
+        KeyBind { key: key.into(), action: action.into() }
+    }
+
+    #[test]
+    fn triggers_normalise_modifiers_and_keys() {
+        let trigger = parse_trigger("global:shift+super+A").unwrap();
+        assert!(trigger.global && !trigger.is_sequence());
+        assert_eq!(trigger.chord(), parse_trigger("global:super+shift+a").unwrap().chord());
+        assert_eq!(parse_trigger("ctrl++").unwrap().sequence[0].key, "+");
+        assert!(parse_trigger("physical:KeyA").unwrap().sequence[0].physical);
+        assert_eq!(parse_trigger("ctrl+a>n").unwrap().sequence.len(), 2);
+        assert_eq!(parse_trigger("ctrl+>").unwrap().sequence[0].key, ">");
+    }
+
+    #[test]
+    fn invalid_triggers_are_rejected() {
+        assert!(parse_trigger("hyper+a").is_err());
+        assert!(parse_trigger("ctrl+ctrl+a").is_err());
+        assert!(parse_trigger("ctrl+nokey").is_err());
+        assert!(parse_trigger("all:").is_err());
+        assert!(parse_trigger("global:ctrl+a>b").is_err());
+    }
+
+    #[test]
+    fn action_parameters_are_checked() {
+        assert_eq!(parse_action("goto_tab:3").unwrap().param.as_deref(), Some("3"));
+        assert!(parse_action("goto_tab").is_err());
+        assert!(parse_action("goto_tab:x").is_err());
+        assert!(parse_action("new_tab:1").is_err());
+        assert!(parse_action("new_split:sideways").is_err());
+        assert!(parse_action("text:").is_err());
+        assert!(parse_action("increase_font_size").is_ok());
+        assert!(parse_action("Not An Action").is_err());
+    }
+
+    #[test]
+    fn actions_missing_from_the_table_are_checked_against_ghostty() {
+        let action = parse_action("toggle_background_opacity").unwrap();
+        assert_eq!(action.name, "toggle_background_opacity");
+        let (level, _) = unknown_action(&action.name, None).unwrap();
+        assert_eq!(level, DiagnosticLevel::Warning);
+        let installed = vec!["toggle_background_opacity".to_string()];
+        assert!(unknown_action(&action.name, Some(&installed)).is_none());
+        let (level, message) = unknown_action("made_up", Some(&installed)).unwrap();
+        assert_eq!((level, message.as_str()), (DiagnosticLevel::Error, "unknown action 'made_up'"));
+    }
+
+    #[test]
+    fn check_reports_overrides_and_shared_leaders() {
+        let diagnostics = check(&[
+            bind("ctrl+a", "new_tab"),
+            bind("ctrl+a", "unbind"),
+            bind("ctrl+b", "new_tab"),
+            bind("ctrl+b>n", "next_tab"),
+            bind("ctrl+c", "goto_tab:x"),
+        ]);
+        let at = |index: usize| diagnostics.iter().filter(|d| d.index == index).collect::<Vec<_>>();
+        assert!(at(0)[0].message.starts_with("removed by the unbind in entry 2"));
+        assert!(at(2)[0].message.contains("share a leader key"));
+        assert_eq!(at(4)[0].level, DiagnosticLevel::Error);
+        assert!(at(1).is_empty() && at(3).is_empty());
+    }
+
+    #[test]
+    fn keybinds_are_written_in_list_order() {
+        let mut config = crate::config::schema::GhosttyConfig::default();
+        config.ghostty.keybinds = vec![
+            bind("ctrl+a", "new_tab"),
+            bind("ctrl+u", "scroll_page_up"),
+            bind("ctrl+a", "scroll_to_top"),
+        ];
+        let rendered = crate::generator::engine::render_apply_template(&config, "apply/ghostty_keybinds.tera").unwrap();
+        let lines: Vec<&str> = rendered.lines().filter(|l| l.starts_with("keybind = ")).collect();
+        assert_eq!(lines, ["keybind = ctrl+a=new_tab", "keybind = ctrl+u=scroll_page_up", "keybind = ctrl+a=scroll_to_top"]);
+        // The entry check calls overridden is the one Ghostty drops
+        let diagnostics = check(&config.ghostty.keybinds);
+        assert!(diagnostics[0].index == 0 && diagnostics[0].message.starts_with("overridden by entry 3"));
+    }
+
+    #[test]
+    fn the_default_keybinds_are_clean() {
+        let config = crate::config::schema::GhosttyConfig::default();
+        let diagnostics = check(&config.ghostty.keybinds);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics.iter().map(|d| d.to_string()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn modifier_order_does_not_hide_an_override() {
+        let keybinds = [bind("shift+super+d", "new_split:down"), bind("super+shift+d", "new_split:right")];
+        let diagnostics = check(&keybinds);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "Keybind shift+super+d=new_split:down: overridden by entry 2 (super+shift+d=new_split:right)"
+        );
+    }
+}
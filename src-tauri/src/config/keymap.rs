@@ -0,0 +1,401 @@
+use super::keybind::{KeyPress, Modifier};
+use super::schema::GhosttyConfig;
+use crate::generator::engine::render_apply_template;
+use serde::{Deserialize, Serialize};
+
+/// Where a key is intercepted. Earlier layers see the key first: Ghostty,
+/// then tmux's root table, then the shell running inside the pane. The
+/// prefix table is only reached after the tmux prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Layer {
+    Terminal,
+    TmuxRoot,
+    TmuxPrefix,
+    Shell,
+}
+
+impl Layer {
+    /// Whether keys bound in `self` never reach `inner`.
+    fn shadows(self, inner: Layer) -> bool {
+        matches!(
+            (self, inner),
+            (Layer::Terminal, Layer::TmuxRoot | Layer::TmuxPrefix | Layer::Shell) | (Layer::TmuxRoot, Layer::Shell)
+        )
+    }
+}
+
+/// A binding from any tool, normalized to Ghostty's key-chord notation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoundKey {
+    pub tool: String,
+    pub layer: Layer,
+    pub keys: Vec<KeyPress>,
+    pub action: String,
+    /// Where it comes from: a config field such as `tmux.prefix_key`, or a
+    /// line of a rendered file such as `.tmux.conf:42`.
+    pub source: String,
+}
+
+impl BoundKey {
+    pub fn chord(&self) -> String {
+        self.keys.iter().map(|k| k.to_string()).collect::<Vec<_>>().join(">")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    /// Two bindings in the same layer; the later one wins.
+    Conflict,
+    /// An outer layer consumes a key an inner binding needs.
+    Unreachable,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyConflict {
+    pub kind: ConflictKind,
+    pub winner: BoundKey,
+    pub shadowed: BoundKey,
+    pub message: String,
+}
+
+fn press(mods: &[Modifier], key: &str) -> KeyPress {
+    let mut mods = mods.to_vec();
+    mods.sort();
+    mods.dedup();
+    KeyPress { mods, key: key.to_string(), physical: false }
+}
+
+// ── Notation parsers ────────────────────────────────────────────────
+
+/// tmux key names: `C-a`, `M-x`, `S-Up`, `H` (shift+h), `BSpace`, `F5`.
+pub fn parse_tmux_key(text: &str) -> Option<KeyPress> {
+    let mut mods = Vec::new();
+    let mut rest = text.trim();
+    while rest.len() > 2 {
+        let modifier = match rest.get(..2) {
+            Some("C-") => Modifier::Ctrl,
+            Some("M-") => Modifier::Alt,
+            Some("S-") => Modifier::Shift,
+            _ => break,
+        };
+        mods.push(modifier);
+        rest = &rest[2..];
+    }
+    let key = match rest {
+        "" => return None,
+        "Up" | "Down" | "Left" | "Right" | "Home" | "End" | "Tab" | "Enter" | "Space" | "Escape" => rest.to_lowercase(),
+        "BSpace" => "backspace".into(),
+        "PageUp" | "PPage" => "page_up".into(),
+        "PageDown" | "NPage" => "page_down".into(),
+        "IC" => "insert".into(),
+        "DC" => "delete".into(),
+        k if k.starts_with('F') && k[1..].parse::<u8>().is_ok() => k.to_lowercase(),
+        k if k.chars().count() == 1 => {
+            let c = k.chars().next()?;
+            if c.is_ascii_uppercase() {
+                mods.push(Modifier::Shift);
+            }
+            c.to_ascii_lowercase().to_string()
+        }
+        _ => return None,
+    };
+    Some(press(&mods, &key))
+}
+
+/// zsh `bindkey` strings: `^A`, `\ef` / `^[f` (alt), `^[[A` (arrows), `^X^E`.
+pub fn parse_zsh_keys(text: &str) -> Option<Vec<KeyPress>> {
+    let mut keys = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let (escaped, after) = match rest.strip_prefix("^[").or_else(|| rest.strip_prefix("\\e")) {
+            Some(after) => (true, after),
+            None => (false, rest),
+        };
+        if escaped {
+            if let Some(arrow) = after.strip_prefix('[').or_else(|| after.strip_prefix('O')) {
+                let key = match arrow.chars().next()? {
+                    'A' => "up",
+                    'B' => "down",
+                    'C' => "right",
+                    'D' => "left",
+                    'H' => "home",
+                    'F' => "end",
+                    _ => return None,
+                };
+                keys.push(press(&[], key));
+                rest = &arrow[1..];
+                continue;
+            }
+            let c = after.chars().next()?;
+            keys.push(press(&[Modifier::Alt], &c.to_ascii_lowercase().to_string()));
+            rest = &after[c.len_utf8()..];
+        } else if let Some(after) = rest.strip_prefix('^') {
+            let c = after.chars().next()?;
+            keys.push(press(&[Modifier::Ctrl], &c.to_ascii_lowercase().to_string()));
+            rest = &after[c.len_utf8()..];
+        } else {
+            let c = rest.chars().next()?;
+            keys.push(press(&[], &c.to_string()));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    Some(keys)
+}
+
+// ── Collection ──────────────────────────────────────────────────────
+
+/// Root-table bindings added by vim-tmux-navigator.
+const TMUX_NAVIGATOR_BINDINGS: &[&str] = &["C-h", "C-j", "C-k", "C-l", "C-\\"];
+
+/// The first word of `text`, unquoted, and the text after it.
+fn shell_word(text: &str) -> Option<(String, &str)> {
+    let text = text.trim_start();
+    let quote = text.chars().next()?;
+    if quote == '\'' || quote == '"' {
+        let end = text[1..].find(quote)? + 1;
+        return Some((text[1..end].to_string(), &text[end + 1..]));
+    }
+    let end = text.find(char::is_whitespace).unwrap_or(text.len());
+    Some((text[..end].to_string(), &text[end..]))
+}
+
+/// A `bind`/`unbind` line of tmux.conf as `(unbind, layer, key, command)`.
+/// Tables other than root and prefix (e.g. copy-mode-vi) give no layer.
+fn parse_tmux_bind(line: &str) -> Option<(bool, Option<Layer>, String, String)> {
+    let (command, mut rest) = shell_word(line)?;
+    let unbind = match command.as_str() {
+        "bind" | "bind-key" => false,
+        "unbind" | "unbind-key" => true,
+        _ => return None,
+    };
+    let mut layer = Some(Layer::TmuxPrefix);
+    loop {
+        let (word, after) = shell_word(rest)?;
+        rest = after;
+        // A lone "-" is the key, not a flag
+        if !word.starts_with('-') || word.len() == 1 {
+            let action = rest.split("\\;").next().unwrap_or_default().trim().to_string();
+            return Some((unbind, layer, word, action));
+        }
+        if word.contains('n') {
+            layer = Some(Layer::TmuxRoot);
+        }
+        if word.contains('T') {
+            let (table, after) = shell_word(rest)?;
+            rest = after;
+            layer = match table.as_str() {
+                "root" => Some(Layer::TmuxRoot),
+                "prefix" => Some(Layer::TmuxPrefix),
+                _ => None,
+            };
+        }
+        if word.contains('N') {
+            rest = shell_word(rest)?.1;
+        }
+    }
+}
+
+/// The `bindkey KEYS WIDGET` statements of a line of .zshrc.
+fn parse_zsh_bindkeys(line: &str) -> Vec<(String, String)> {
+    line.split(';')
+        .filter_map(|statement| {
+            let (command, rest) = shell_word(statement)?;
+            let (keys, rest) = shell_word(rest)?;
+            // Keymap options such as -M or -v
+            if command != "bindkey" || keys.starts_with('-') {
+                return None;
+            }
+            Some((keys, shell_word(rest)?.0))
+        })
+        .collect()
+}
+
+fn tmux_plugin_enabled(config: &GhosttyConfig, name: &str) -> bool {
+    config.tmux.plugins.iter().any(|p| p.enabled && p.name == name)
+}
+
+/// Every binding the generated configs set up, in the order each tool
+/// applies them (later entries in the same layer override earlier ones).
+pub fn collect(config: &GhosttyConfig) -> Vec<BoundKey> {
+    let mut bound = Vec::new();
+    let mut push = |tool: &str, layer: Layer, keys: Option<Vec<KeyPress>>, action: &str, source: String| {
+        if let Some(keys) = keys.filter(|k| !k.is_empty()) {
+            bound.push(BoundKey { tool: tool.into(), layer, keys, action: action.into(), source });
+        }
+    };
+
+    if config.ghostty.enabled {
+        for (i, kb) in config.ghostty.keybinds.iter().enumerate() {
+            let Ok(parsed) = kb.parse() else { continue };
+            // Unconsumed bindings still pass the key on
+            if parsed.trigger.unconsumed || parsed.action.name == "unbind" {
+                continue;
+            }
+            push("ghostty", Layer::Terminal, Some(parsed.trigger.sequence), &kb.action, format!("ghostty.keybinds[{}]", i));
+        }
+    }
+
+    if config.tmux.enabled {
+        let tmux = &config.tmux;
+        let one = |key: &str| parse_tmux_key(key).map(|k| vec![k]);
+        push("tmux", Layer::TmuxRoot, one(&tmux.prefix_key), "prefix", "tmux.prefix_key".into());
+        if tmux_plugin_enabled(config, "vim-tmux-navigator") {
+            for key in TMUX_NAVIGATOR_BINDINGS {
+                push("vim-tmux-navigator", Layer::TmuxRoot, one(key), "select-pane", "tmux.plugins".into());
+            }
+        }
+        // Read from the rendered file, so template overrides are included
+        let conf = render_apply_template(config, "apply/tmux_config.tera").unwrap_or_default();
+        let mut conf_bindings: Vec<(Layer, String, String, usize)> = Vec::new();
+        for (n, line) in conf.lines().enumerate() {
+            let Some((unbind, Some(layer), key, action)) = parse_tmux_bind(line.trim()) else { continue };
+            if unbind {
+                conf_bindings.retain(|(l, k, _, _)| !(*l == layer && *k == key));
+            } else {
+                conf_bindings.push((layer, key, action, n + 1));
+            }
+        }
+        for (layer, key, action, line) in conf_bindings {
+            push("tmux", layer, one(&key), &action, format!(".tmux.conf:{}", line));
+        }
+        // Plugins bind when tpm runs, at the end of tmux.conf
+        if tmux_plugin_enabled(config, "tmux-floax") {
+            push("tmux-floax", Layer::TmuxPrefix, one(&tmux.floax_bind), "floating pane", "tmux.floax_bind".into());
+        }
+        if tmux_plugin_enabled(config, "tmux-sessionx") {
+            push("tmux-sessionx", Layer::TmuxPrefix, one(&tmux.sessionx_bind), "session picker", "tmux.sessionx_bind".into());
+        }
+    }
+
+    if config.zshrc.enabled {
+        let atuin = config.packages.packages.iter().any(|p| p.enabled && p.name == "atuin");
+        if atuin {
+            push("atuin", Layer::Shell, parse_zsh_keys("^R"), "search", "atuin init".into());
+            let up = format!("up-search ({})", config.tool_configs.atuin.filter_mode_shell_up_key_binding);
+            push("atuin", Layer::Shell, parse_zsh_keys("^[[A"), &up, "tool_configs.atuin.filter_mode_shell_up_key_binding".into());
+        }
+        if config.zshrc.vi_mode_enabled {
+            push("zsh-vi-mode", Layer::Shell, parse_zsh_keys(&config.zshrc.vi_mode_escape_key), "escape to normal mode", "zshrc.vi_mode_escape_key".into());
+        }
+        let zshrc = render_apply_template(config, "apply/zshrc_config.tera").unwrap_or_default();
+        for (n, line) in zshrc.lines().enumerate() {
+            for (keys, widget) in parse_zsh_bindkeys(line) {
+                push("zsh", Layer::Shell, parse_zsh_keys(&keys), &widget, format!(".zshrc:{}", n + 1));
+            }
+        }
+    }
+    bound
+}
+
+// ── Analysis ────────────────────────────────────────────────────────
+
+fn describe(b: &BoundKey) -> String {
+    let chord = if b.layer == Layer::TmuxPrefix { format!("prefix {}", b.chord()) } else { b.chord() };
+    format!("{} {} ({})", b.tool, chord, b.action)
+}
+
+/// Report bindings that override each other within a layer, and bindings an
+/// outer layer makes unreachable (e.g. a Ghostty keybind eating the tmux prefix).
+pub fn analyze(config: &GhosttyConfig) -> Vec<KeyConflict> {
+    let bound = collect(config);
+    let mut conflicts = Vec::new();
+
+    for (i, outer) in bound.iter().enumerate() {
+        for (j, inner) in bound.iter().enumerate() {
+            // Already covered by the swallowed-prefix report
+            let send_prefix = inner.layer == Layer::TmuxPrefix && inner.action == "send-prefix";
+            if outer.layer.shadows(inner.layer) && !send_prefix && inner.keys.contains(&outer.keys[0]) {
+                let message = if inner.layer == Layer::TmuxRoot && inner.action == "prefix" {
+                    format!("{} swallows the tmux prefix {}", describe(outer), inner.chord())
+                } else {
+                    format!("{} is unreachable: {} consumes {}", describe(inner), describe(outer), outer.keys[0])
+                };
+                conflicts.push(KeyConflict { kind: ConflictKind::Unreachable, winner: outer.clone(), shadowed: inner.clone(), message });
+            } else if j > i
+                && outer.layer == inner.layer
+                // Ghostty's own duplicates are reported by keybind::check
+                && outer.layer != Layer::Terminal
+                && (outer.keys.starts_with(&inner.keys) || inner.keys.starts_with(&outer.keys))
+            {
+                let message = format!("{} is overridden by {}", describe(outer), describe(inner));
+                conflicts.push(KeyConflict { kind: ConflictKind::Conflict, winner: inner.clone(), shadowed: outer.clone(), message });
+            }
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tmux_bind_lines_parse_flags_and_tables() {
+        let bind = parse_tmux_bind;
+        assert_eq!(bind("bind -r H resize-pane -L 5"), Some((false, Some(Layer::TmuxPrefix), "H".into(), "resize-pane -L 5".into())));
+        assert_eq!(bind("bind - split-window -v"), Some((false, Some(Layer::TmuxPrefix), "-".into(), "split-window -v".into())));
+        assert_eq!(bind("bind-key -n C-h select-pane -L").unwrap().1, Some(Layer::TmuxRoot));
+        assert_eq!(bind("bind -T copy-mode-vi v send -X begin-selection").unwrap().1, None);
+        assert_eq!(bind("bind -N 'a note' r source-file x \\; display-message hi").unwrap().3, "source-file x");
+        assert_eq!(bind("unbind '\"'"), Some((true, Some(Layer::TmuxPrefix), "\"".into(), String::new())));
+        assert_eq!(bind("set -g prefix C-a"), None);
+    }
+
+    #[test]
+    fn zsh_bindkey_statements_are_split() {
+        assert_eq!(
+            parse_zsh_bindkeys("bindkey '^X^E' edit-command-line; autoload -z edit-command-line; bindkey -M vicmd 'k' up"),
+            vec![("^X^E".to_string(), "edit-command-line".to_string())]
+        );
+    }
+
+    #[test]
+    fn bindings_come_from_the_rendered_files() {
+        let config = GhosttyConfig::default();
+        let bound = collect(&config);
+        let find = |tool: &str, action: &str| bound.iter().find(|b| b.tool == tool && b.action.starts_with(action));
+        let split = find("tmux", "split-window -h").unwrap();
+        assert_eq!((split.layer, split.chord()), (Layer::TmuxPrefix, "|".to_string()));
+        assert!(split.source.starts_with(".tmux.conf:"));
+        for popup in &config.tmux.popup_bindings {
+            assert!(bound.iter().any(|b| b.layer == Layer::TmuxPrefix && b.action.contains(&popup.command)), "{}", popup.command);
+        }
+        assert_eq!(find("zsh", "edit-command-line").unwrap().chord(), "ctrl+x>ctrl+e");
+        // Copy-mode bindings are not part of the key layers
+        assert!(find("tmux", "send -X").is_none());
+    }
+
+    #[test]
+    fn tmux_and_zsh_notation_become_chords() {
+        let chord = |keys: Vec<KeyPress>| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>().join(">");
+        let tmux = |key: &str| parse_tmux_key(key).map(|k| chord(vec![k]));
+        assert_eq!(tmux("C-a").as_deref(), Some("ctrl+a"));
+        assert_eq!(tmux("M-S-Up").as_deref(), Some("shift+alt+up"));
+        assert_eq!(tmux("H").as_deref(), Some("shift+h"));
+        assert_eq!(tmux("BSpace").as_deref(), Some("backspace"));
+        assert_eq!(tmux("F5").as_deref(), Some("f5"));
+        assert_eq!(tmux("Foo"), None);
+
+        let zsh = |keys: &str| parse_zsh_keys(keys).map(chord);
+        assert_eq!(zsh("^[[A").as_deref(), Some("up"));
+        assert_eq!(zsh("\\ef").as_deref(), Some("alt+f"));
+        assert_eq!(zsh("^X^E").as_deref(), Some("ctrl+x>ctrl+e"));
+    }
+
+    #[test]
+    fn outer_layers_and_later_bindings_are_reported() {
+        let mut config = GhosttyConfig::default();
+        config.ghostty.keybinds.push(crate::config::sections::KeyBind { key: "ctrl+a".into(), action: "new_tab".into() });
+        config.tmux.floax_bind = "|".into();
+        let conflicts = analyze(&config);
+        let find = |kind: ConflictKind, message: &str| conflicts.iter().any(|c| c.kind == kind && c.message == message);
+
+        assert!(find(ConflictKind::Unreachable, "ghostty ctrl+a (new_tab) swallows the tmux prefix ctrl+a"), "{:#?}", conflicts);
+        assert!(find(ConflictKind::Unreachable, "zsh ctrl+a (beginning-of-line) is unreachable: tmux ctrl+a (prefix) consumes ctrl+a"));
+        let floax = conflicts.iter().find(|c| c.winner.tool == "tmux-floax").unwrap();
+        assert_eq!(floax.kind, ConflictKind::Conflict);
+        assert!(floax.shadowed.action.starts_with("split-window -h"), "{}", floax.message);
+    }
+}
@@ -2,3 +2,7 @@ pub mod schema;
 pub mod palette;
 pub mod sections;
 pub mod defaults;
+pub mod catalog;
+pub mod keybind;
+pub mod keymap;
+pub mod requirements;
@@ -53,6 +53,39 @@ impl Default for AyuPalette {
     }
 }
 
+fn parse_hex(hex: &str) -> Option<[u8; 3]> {
+    let hex = hex.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// Mix `fg` into `bg` by `amount` (0.0 = `bg`, 1.0 = `fg`), as `#rrggbb`.
+/// Returns `None` if either color is not a 6-digit hex color.
+pub fn blend(bg: &str, fg: &str, amount: f32) -> Option<String> {
+    let (bg, fg) = (parse_hex(bg)?, parse_hex(fg)?);
+    let amount = amount.clamp(0.0, 1.0);
+    let mix = |i: usize| (bg[i] as f32 + (fg[i] as f32 - bg[i] as f32) * amount).round() as u8;
+    Some(format!("#{:02x}{:02x}{:02x}", mix(0), mix(1), mix(2)))
+}
+
+pub fn default_palette_name() -> String {
+    "Ayu Dark".into()
+}
+
+/// Ghostty theme file name for a palette: "Tokyo Night" -> "tokyo-night".
+pub fn theme_file_name(name: &str) -> String {
+    let slug = name
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() { "ghostty-ultimate".into() } else { slug }
+}
+
 pub fn builtin_palettes() -> Vec<NamedPalette> {
     vec![
         NamedPalette {
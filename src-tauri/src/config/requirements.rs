@@ -0,0 +1,449 @@
+use super::schema::GhosttyConfig;
+use super::sections::PackageEntry;
+use crate::generator::{custom, registry};
+use serde::{Deserialize, Serialize};
+
+/// Something a section, or one of its aliases, functions or plugins, needs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Requirement {
+    /// An entry in the package list, e.g. `git-delta`.
+    Package(String),
+    /// A font installed by the packages section.
+    Font(String),
+    /// Another section.
+    Section(String),
+}
+
+pub const JETBRAINS_MONO: &str = "JetBrains Mono";
+pub const NERD_FONT: &str = "Nerd Font";
+
+/// One edge of the requirement graph.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequirementEdge {
+    pub section: String,
+    /// The alias, function, plugin or setting that needs it; `None` when
+    /// the section itself does.
+    pub item: Option<String>,
+    pub requirement: Requirement,
+    pub satisfied: bool,
+    pub message: String,
+}
+
+impl RequirementEdge {
+    fn new(config: &GhosttyConfig, section: &str, item: Option<String>, requirement: Requirement) -> Self {
+        let who = match &item {
+            Some(item) => format!("{} ({})", item, section),
+            None => format!("Section '{}'", section),
+        };
+        let what = match &requirement {
+            Requirement::Package(name) => format!("package '{}', which is not enabled", name),
+            Requirement::Font(name) => format!("font '{}', which Packages does not install", name),
+            Requirement::Section(id) => format!("section '{}', which is disabled", id),
+        };
+        RequirementEdge {
+            section: section.to_string(),
+            satisfied: is_met(config, &requirement),
+            message: format!("{} needs {}", who, what),
+            item,
+            requirement,
+        }
+    }
+}
+
+/// Result of `auto_fix`, returned to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequirementFix {
+    pub config: GhosttyConfig,
+    pub changes: Vec<String>,
+}
+
+// ── Requirement tables ──────────────────────────────────────────────
+
+/// Static form of `Requirement` for the tables below.
+#[derive(Clone, Copy, PartialEq)]
+enum Need {
+    Package(&'static str),
+    Font(&'static str),
+    Section(&'static str),
+}
+
+impl Need {
+    fn owned(self) -> Requirement {
+        match self {
+            Need::Package(name) => Requirement::Package(name.into()),
+            Need::Font(name) => Requirement::Font(name.into()),
+            Need::Section(id) => Requirement::Section(id.into()),
+        }
+    }
+}
+
+/// What each built-in section needs whenever it is enabled. Needs that
+/// depend on a setting are added in `section_needs`.
+const SECTION_NEEDS: &[(&str, &[Need])] = &[
+    ("starship", &[Need::Package("starship"), Need::Font(NERD_FONT)]),
+    ("fastfetch", &[Need::Package("fastfetch")]),
+    ("zsh_plugins", &[Need::Package("yazi")]),
+    ("tmux", &[Need::Package("tmux")]),
+    ("neovim", &[Need::Package("neovim")]),
+    ("tool_configs", &[Need::Package("atuin"), Need::Package("lazygit")]),
+    // core.pager is always delta
+    ("git", &[Need::Package("git-delta")]),
+    ("themes", &[Need::Package("btop")]),
+    ("tmux_scripts", &[Need::Package("tmux")]),
+    // .zshrc runs the starship/zoxide/atuin inits, sources fzf and the plugins
+    (
+        "zshrc",
+        &[
+            Need::Package("starship"),
+            Need::Package("zoxide"),
+            Need::Package("atuin"),
+            Need::Package("fzf"),
+            Need::Section("zsh_plugins"),
+        ],
+    ),
+];
+
+/// Zsh, tmux and Neovim plugins that shell out to other tools.
+const PLUGIN_NEEDS: &[(&str, &[Need])] = &[
+    ("fzf-tab", &[Need::Package("fzf")]),
+    ("tmux-fzf", &[Need::Package("fzf")]),
+    ("tmux-sessionx", &[Need::Package("fzf")]),
+];
+
+/// Commands that aliases, functions and settings may run, and what
+/// provides them.
+const COMMAND_NEEDS: &[(&str, Need)] = &[
+    ("starship", Need::Package("starship")),
+    ("eza", Need::Package("eza")),
+    ("bat", Need::Package("bat")),
+    ("fzf", Need::Package("fzf")),
+    ("rg", Need::Package("ripgrep")),
+    ("fd", Need::Package("fd")),
+    ("zoxide", Need::Package("zoxide")),
+    ("tmux", Need::Package("tmux")),
+    ("nvim", Need::Package("neovim")),
+    ("atuin", Need::Package("atuin")),
+    ("yazi", Need::Package("yazi")),
+    ("lazygit", Need::Package("lazygit")),
+    ("gh", Need::Package("gh")),
+    ("trash", Need::Package("trash-cli")),
+    ("fastfetch", Need::Package("fastfetch")),
+    ("btop", Need::Package("btop")),
+    ("delta", Need::Package("git-delta")),
+    ("dust", Need::Package("dust")),
+    ("procs", Need::Package("procs")),
+    ("hyperfine", Need::Package("hyperfine")),
+    ("tokei", Need::Package("tokei")),
+    ("gping", Need::Package("gping")),
+    ("curlie", Need::Package("curlie")),
+    ("tlrc", Need::Package("tlrc")),
+    ("ffmpeg", Need::Package("ffmpeg")),
+    ("7zz", Need::Package("sevenzip")),
+    ("pdftotext", Need::Package("poppler")),
+    ("jq", Need::Package("jq")),
+    ("tmux-ai", Need::Section("tmux_scripts")),
+    ("tmux-pair", Need::Section("tmux_scripts")),
+    ("tmux-review", Need::Section("tmux_scripts")),
+    ("tmux-dev", Need::Section("tmux_scripts")),
+    ("tmux-cheat", Need::Section("tmux_scripts")),
+];
+
+// ── Graph ───────────────────────────────────────────────────────────
+
+/// What a shell command line needs, by the commands it mentions.
+fn command_needs(command: &str) -> Vec<Need> {
+    let mut needs: Vec<Need> = Vec::new();
+    let words = command.split(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')));
+    for word in words {
+        if let Some((_, need)) = COMMAND_NEEDS.iter().find(|(cmd, _)| *cmd == word) {
+            if !needs.contains(need) {
+                needs.push(*need);
+            }
+        }
+    }
+    // eza's icons are Nerd Font glyphs
+    if command.contains("--icons") {
+        needs.push(Need::Font(NERD_FONT));
+    }
+    needs
+}
+
+fn plugin_needs(name: &str) -> &'static [Need] {
+    PLUGIN_NEEDS.iter().find(|(plugin, _)| *plugin == name).map_or(&[], |(_, needs)| *needs)
+}
+
+/// Every (item, requirement) pair of an enabled built-in section.
+fn section_needs(config: &GhosttyConfig, id: &str) -> Vec<(Option<String>, Need)> {
+    let mut needs: Vec<(Option<String>, Need)> = SECTION_NEEDS
+        .iter()
+        .filter(|(section, _)| *section == id)
+        .flat_map(|(_, needs)| needs.iter().map(|n| (None, *n)))
+        .collect();
+    let mut item = |label: String, found: Vec<Need>| {
+        needs.extend(found.into_iter().map(|n| (Some(label.clone()), n)));
+    };
+
+    match id {
+        "ghostty" => {
+            let g = &config.ghostty;
+            let families = [&g.font_family, &g.font_family_bold, &g.font_family_italic, &g.font_family_bold_italic];
+            if families.iter().any(|f| f.starts_with(JETBRAINS_MONO)) {
+                item("font-family".into(), vec![Need::Font(JETBRAINS_MONO)]);
+            }
+            if families.iter().any(|f| f.contains(NERD_FONT)) {
+                item("font-family".into(), vec![Need::Font(NERD_FONT)]);
+            }
+        }
+        "zsh_plugins" => {
+            for plugin in config.zsh_plugins.plugins.iter().filter(|p| p.enabled) {
+                item(format!("Plugin '{}'", plugin.name), plugin_needs(&plugin.name).to_vec());
+            }
+        }
+        "tmux" => {
+            let tmux = &config.tmux;
+            for plugin in tmux.plugins.iter().filter(|p| p.enabled) {
+                item(format!("Plugin '{}'", plugin.name), plugin_needs(&plugin.name).to_vec());
+                if plugin.name == "tmux-sessionx" && tmux.sessionx_zoxide_mode {
+                    item("SessionX zoxide mode".into(), vec![Need::Package("zoxide")]);
+                }
+            }
+            for popup in &tmux.popup_bindings {
+                item(format!("Popup '{}'", popup.key), command_needs(&popup.command));
+            }
+        }
+        "neovim" => {
+            for plugin in config.neovim.plugins.iter().filter(|p| p.enabled) {
+                item(format!("Plugin '{}'", plugin.name), plugin_needs(&plugin.name).to_vec());
+            }
+        }
+        "tool_configs" => {
+            let lazygit = &config.tool_configs.lazygit;
+            item("lazygit pager".into(), command_needs(&lazygit.pager));
+            item("lazygit edit preset".into(), command_needs(&lazygit.edit_preset));
+            if !lazygit.nerd_fonts_version.is_empty() {
+                item("lazygit icons".into(), vec![Need::Font(NERD_FONT)]);
+            }
+        }
+        "git" => {
+            item("core.editor".into(), command_needs(&config.git.core_editor));
+            for alias in config.git.aliases.iter().filter(|a| a.enabled && a.command.starts_with('!')) {
+                item(format!("Git alias '{}'", alias.name), command_needs(&alias.command));
+            }
+        }
+        "themes" => {
+            if config.themes.bat_theme_enabled {
+                item("bat theme".into(), vec![Need::Package("bat")]);
+            }
+            if config.themes.yazi_theme_enabled {
+                item("yazi theme".into(), vec![Need::Package("yazi")]);
+            }
+        }
+        "zshrc" => {
+            let zshrc = &config.zshrc;
+            for alias in zshrc.aliases.iter().filter(|a| a.enabled) {
+                item(format!("Alias '{}'", alias.name), command_needs(&alias.command));
+            }
+            for function in zshrc.functions.iter().filter(|f| f.enabled) {
+                item(format!("Function '{}'", function.name), command_needs(&function.body));
+            }
+            item("EDITOR".into(), command_needs(&zshrc.editor));
+            if zshrc.manpager_enabled {
+                item("MANPAGER".into(), vec![Need::Package("bat")]);
+            }
+            if zshrc.show_fastfetch_on_start {
+                item("Fastfetch on start".into(), vec![Need::Package("fastfetch")]);
+            }
+            if zshrc.show_tmux_cheat_on_start {
+                item("tmux cheat sheet on start".into(), vec![Need::Section("tmux_scripts")]);
+            }
+        }
+        _ => {}
+    }
+    needs
+}
+
+fn is_met(config: &GhosttyConfig, requirement: &Requirement) -> bool {
+    match requirement {
+        Requirement::Package(name) => config.packages.packages.iter().any(|p| p.enabled && p.name == *name),
+        Requirement::Font(name) if name == JETBRAINS_MONO => config.packages.install_jetbrains_font,
+        // The Nerd Font variant is installed alongside JetBrains Mono
+        Requirement::Font(name) if name == NERD_FONT => {
+            config.packages.install_jetbrains_font && config.packages.install_nerd_font
+        }
+        // Other fonts are not managed here
+        Requirement::Font(_) => true,
+        Requirement::Section(id) => registry::is_enabled(config, id),
+    }
+}
+
+/// Every requirement of every enabled section, built-in and custom.
+pub fn graph(config: &GhosttyConfig) -> Vec<RequirementEdge> {
+    let mut edges = Vec::new();
+    for def in registry::SECTIONS.iter().filter(|s| (s.enabled)(config)) {
+        for (item, need) in section_needs(config, def.id) {
+            let requirement = need.owned();
+            let duplicate = edges.iter().any(|e: &RequirementEdge| {
+                e.section == def.id && e.item == item && e.requirement == requirement
+            });
+            if !duplicate {
+                edges.push(RequirementEdge::new(config, def.id, item, requirement));
+            }
+        }
+    }
+    let customs = custom::registry();
+    for manifest in customs.sections.iter().filter(|m| custom::is_enabled(config, &m.id)) {
+        for requirement in &manifest.requires {
+            edges.push(RequirementEdge::new(config, &manifest.id, None, requirement.clone()));
+        }
+    }
+    edges
+}
+
+/// Requirements of enabled sections that the config does not meet.
+pub fn unmet(config: &GhosttyConfig) -> Vec<RequirementEdge> {
+    graph(config).into_iter().filter(|e| !e.satisfied).collect()
+}
+
+// ── Auto-fix ────────────────────────────────────────────────────────
+
+/// Turn on whatever provides `requirement`. Returns a description of the
+/// change, or `None` when it cannot be met from the config.
+fn provide(config: &mut GhosttyConfig, requirement: &Requirement) -> Option<String> {
+    match requirement {
+        Requirement::Package(name) => {
+            if let Some(entry) = config.packages.packages.iter_mut().find(|p| p.name == *name) {
+                entry.enabled = true;
+                return Some(format!("Enabled package '{}'", name));
+            }
+            let category = GhosttyConfig::default()
+                .packages
+                .packages
+                .into_iter()
+                .find(|p| p.name == *name)
+                .map_or_else(|| "core".to_string(), |p| p.category);
+            config.packages.packages.push(PackageEntry { name: name.clone(), enabled: true, category });
+            Some(format!("Added package '{}'", name))
+        }
+        Requirement::Font(name) => {
+            config.packages.install_jetbrains_font = true;
+            if name == NERD_FONT {
+                config.packages.install_nerd_font = true;
+            }
+            Some(format!("Enabled font '{}'", name))
+        }
+        Requirement::Section(id) => {
+            if let Some(def) = registry::builtin(id) {
+                (def.set_enabled)(config, true);
+            } else if custom::registry().get(id).is_some() {
+                config.custom_sections.entry(id.clone()).or_default().enabled = true;
+            } else {
+                return None;
+            }
+            Some(format!("Enabled section '{}'", id))
+        }
+    }
+}
+
+/// Enable the packages, fonts and sections that enabled sections need,
+/// until nothing more can be fixed. Enabling a section can add new
+/// requirements, so this repeats until it reaches a fixed point.
+pub fn auto_fix(config: &mut GhosttyConfig) -> Vec<String> {
+    let mut changes = Vec::new();
+    loop {
+        let mut fixed = false;
+        for edge in unmet(config) {
+            if is_met(config, &edge.requirement) {
+                continue;
+            }
+            if let Some(change) = provide(config, &edge.requirement) {
+                changes.push(change);
+                fixed = true;
+            }
+        }
+        if !fixed {
+            return changes;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unmet_messages(config: &GhosttyConfig) -> Vec<String> {
+        unmet(config).into_iter().map(|e| e.message).collect()
+    }
+
+    #[test]
+    fn the_default_config_meets_its_requirements() {
+        assert_eq!(unmet_messages(&GhosttyConfig::default()), Vec::<String>::new());
+    }
+
+    #[test]
+    fn commands_need_what_they_run() {
+        let needs: Vec<Requirement> = command_needs("eza --icons | rg -i todo && tmux-ai; batch --rg")
+            .into_iter()
+            .map(Need::owned)
+            .collect();
+        assert_eq!(
+            needs,
+            [
+                Requirement::Package("eza".into()),
+                Requirement::Package("ripgrep".into()),
+                Requirement::Section("tmux_scripts".into()),
+                Requirement::Font(NERD_FONT.into()),
+            ]
+        );
+        assert!(command_needs("echo $EDITOR").is_empty());
+    }
+
+    #[test]
+    fn unmet_requirements_name_the_item_that_needs_them() {
+        let mut config = GhosttyConfig::default();
+        config.packages.packages.retain(|p| p.name != "fzf");
+        config.packages.install_nerd_font = false;
+        let messages = unmet_messages(&config);
+        for expected in [
+            "Plugin 'fzf-tab' (zsh_plugins) needs package 'fzf', which is not enabled",
+            "Section 'zshrc' needs package 'fzf', which is not enabled",
+            "Section 'starship' needs font 'Nerd Font', which Packages does not install",
+        ] {
+            assert!(messages.iter().any(|m| m == expected), "{:?}", messages);
+        }
+
+        // Disabled sections need nothing
+        config.zsh_plugins.enabled = false;
+        config.zshrc.enabled = false;
+        config.tmux.enabled = false;
+        assert!(!unmet(&config).iter().any(|e| e.requirement == Requirement::Package("fzf".into())));
+    }
+
+    #[test]
+    fn auto_fix_follows_new_requirements_to_a_fixed_point() {
+        let mut config = GhosttyConfig::default();
+        config.zsh_plugins.enabled = false;
+        config.tmux_scripts.enabled = false;
+        config.zshrc.show_tmux_cheat_on_start = true;
+        config.packages.packages.retain(|p| p.name != "yazi");
+        config.tmux.popup_bindings.retain(|p| !p.command.contains("yazi"));
+        config.zshrc.functions.retain(|f| !f.body.contains("yazi"));
+        config.zshrc.aliases.retain(|a| !a.command.contains("yazi"));
+        config.themes.yazi_theme_enabled = false;
+        for package in config.packages.packages.iter_mut().filter(|p| p.name == "starship") {
+            package.enabled = false;
+        }
+
+        let changes = auto_fix(&mut config);
+        for expected in ["Enabled package 'starship'", "Enabled section 'zsh_plugins'", "Enabled section 'tmux_scripts'", "Added package 'yazi'"] {
+            assert_eq!(changes.iter().filter(|c| *c == expected).count(), 1, "{:?}", changes);
+        }
+        // Enabling zsh_plugins is what made yazi necessary
+        let position = |change: &str| changes.iter().position(|c| c == change).unwrap();
+        assert!(position("Enabled section 'zsh_plugins'") < position("Added package 'yazi'"));
+        let yazi = config.packages.packages.iter().find(|p| p.name == "yazi").unwrap();
+        assert_eq!(yazi.category, "file");
+        assert!(unmet(&config).is_empty());
+        assert!(auto_fix(&mut config).is_empty());
+    }
+}
@@ -1,10 +1,14 @@
 use serde::{Deserialize, Serialize};
-use super::palette::AyuPalette;
+use super::palette::{self, AyuPalette};
 use super::sections::*;
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GhosttyConfig {
     pub palette: AyuPalette,
+    /// Name of the preset the palette came from; names the Ghostty theme file.
+    #[serde(default = "palette::default_palette_name")]
+    pub palette_name: String,
     pub clean_artifacts: CleanArtifactsConfig,
     pub packages: PackagesConfig,
     pub ghostty: GhosttyTermConfig,
@@ -18,6 +22,9 @@ pub struct GhosttyConfig {
     pub themes: ThemesConfig,
     pub tmux_scripts: TmuxScriptsConfig,
     pub zshrc: ZshrcConfig,
+    /// Sections defined by manifests in ~/.config/ghostty-ultimate-ui/sections.
+    #[serde(default)]
+    pub custom_sections: BTreeMap<String, CustomSectionConfig>,
 }
 
 impl Default for GhosttyConfig {
@@ -26,6 +33,13 @@ impl Default for GhosttyConfig {
     }
 }
 
+impl GhosttyConfig {
+    /// File name of the generated Ghostty theme, e.g. "tokyo-night".
+    pub fn theme_name(&self) -> String {
+        palette::theme_file_name(&self.palette_name)
+    }
+}
+
 pub fn validate(config: &GhosttyConfig) -> Result<Vec<String>, String> {
     let mut warnings = Vec::new();
 
@@ -62,22 +76,78 @@ pub fn validate(config: &GhosttyConfig) -> Result<Vec<String>, String> {
         warnings.push("Scrollback limit is very high, may use excessive memory".into());
     }
 
+    for option in &config.ghostty.extra_options {
+        let key = option.key.trim();
+        if key.is_empty() || key.contains('=') || key.contains(char::is_whitespace) {
+            warnings.push(format!("Ghostty extra option '{}' is not a valid key", option.key));
+        } else if let Err(e) = super::catalog::catalog().validate(key, option.value.trim()) {
+            warnings.push(e);
+        }
+        if option.value.contains('\n') {
+            warnings.push(format!("Ghostty extra option '{}' must be a single line", option.key));
+        }
+    }
+
+    for shader in config.ghostty.shaders.iter().filter(|s| s.enabled) {
+        if !shader.has_valid_name() {
+            warnings.push(format!("Shader '{}' must be a .glsl path inside ~/.config/ghostty/shaders", shader.name));
+        }
+    }
+
+    warnings.extend(super::keybind::check(&config.ghostty.keybinds).iter().map(|d| d.to_string()));
+    warnings.extend(super::keymap::analyze(config).into_iter().map(|c| c.message));
+
     if config.tmux.history_limit > 1_000_000 {
         warnings.push("tmux history limit is very high".into());
     }
 
-    // Check for package dependencies
-    let enabled_pkgs: Vec<&str> = config.packages.packages.iter()
-        .filter(|p| p.enabled)
-        .map(|p| p.name.as_str())
-        .collect();
-
-    if config.zshrc.aliases.iter().any(|a| a.enabled && a.command.contains("eza")) && !enabled_pkgs.contains(&"eza") {
-        warnings.push("Aliases reference 'eza' but it's not in enabled packages".into());
+    // Git identities
+    let mut identity_ids: Vec<&str> = Vec::new();
+    for identity in config.git.identities.iter().filter(|i| i.enabled) {
+        if !identity.has_valid_id() {
+            warnings.push(format!("Git identity id '{}' may only contain letters, digits, '-' and '_'", identity.id));
+        } else if identity_ids.contains(&identity.id.as_str()) {
+            warnings.push(format!("Git identity '{}' is defined more than once", identity.id));
+        }
+        identity_ids.push(&identity.id);
+        if !identity.email.contains('@') {
+            warnings.push(format!("Git identity '{}' has no valid email", identity.id));
+        }
+        if identity.gitdir_patterns().is_empty() {
+            warnings.push(format!("Git identity '{}' has no directories and will never apply", identity.id));
+        }
     }
-    if config.zshrc.aliases.iter().any(|a| a.enabled && a.command.contains("bat")) && !enabled_pkgs.contains(&"bat") {
-        warnings.push("Aliases reference 'bat' but it's not in enabled packages".into());
+
+    if config.git.signing.enabled && config.git.signing.key.trim().is_empty() {
+        warnings.push("Commit signing is enabled but no signing key is set".into());
     }
 
+    warnings.extend(super::requirements::unmet(config).into_iter().map(|e| e.message));
+
+    warnings.extend(crate::generator::custom::validate(config));
+
     Ok(warnings)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extra_options_need_a_bare_key_and_one_line() {
+        let mut config = GhosttyConfig::default();
+        let option = |key: &str, value: &str| GhosttyOption { key: key.into(), value: value.into() };
+        config.ghostty.extra_options =
+            vec![option("window-save-state", "always"), option("bad key", "x"), option("", "x"), option("a", "1\n2")];
+        let warnings = validate(&config).unwrap();
+        let extra: Vec<&String> = warnings.iter().filter(|w| w.starts_with("Ghostty extra option")).collect();
+        assert_eq!(
+            extra,
+            [
+                "Ghostty extra option 'bad key' is not a valid key",
+                "Ghostty extra option '' is not a valid key",
+                "Ghostty extra option 'a' must be a single line",
+            ]
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use super::palette::{blend, AyuPalette};
 use serde::{Deserialize, Serialize};
 
 // ── Section 1: Clean Artifacts ──────────────────────────────────────
@@ -105,6 +106,9 @@ pub struct GhosttyTermConfig {
     pub custom_shader_enabled: bool,
     pub custom_shader_path: String,
     pub install_community_shaders: bool,
+    /// Library shaders chained after `custom_shader_path`, in order.
+    #[serde(default)]
+    pub shaders: Vec<ShaderEntry>,
     // Keybinds
     pub keybinds: Vec<KeyBind>,
     // Quick Terminal
@@ -112,6 +116,45 @@ pub struct GhosttyTermConfig {
     pub quick_terminal_size: String,
     pub quick_terminal_animation_duration: f32,
     pub quick_terminal_autohide: bool,
+    /// Options written verbatim, in order, after everything modeled above.
+    #[serde(default)]
+    pub extra_options: Vec<GhosttyOption>,
+    #[serde(default)]
+    pub layout: GhosttyLayout,
+}
+
+/// How `~/.config/ghostty/config` is laid out on disk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GhosttyLayout {
+    /// Everything in one file.
+    #[default]
+    Single,
+    /// A base config pulling in `fonts`, `appearance`, `keybinds` and `extra`
+    /// through `config-file`, plus a user-owned `local` that is loaded last.
+    Modular,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GhosttyOption {
+    pub key: String,
+    pub value: String,
+}
+
+/// A shader from `~/.config/ghostty/shaders`, by path relative to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShaderEntry {
+    pub name: String,
+    pub enabled: bool,
+}
+
+impl ShaderEntry {
+    /// A plain relative `.glsl` path that cannot leave the library.
+    pub fn has_valid_name(&self) -> bool {
+        let path = std::path::Path::new(&self.name);
+        self.name.ends_with(".glsl")
+            && path.components().all(|c| matches!(c, std::path::Component::Normal(_)))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -373,6 +416,9 @@ pub struct LazygitConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitConfig {
     pub enabled: bool,
+    /// How the managed include file is combined with the user's own gitconfig.
+    #[serde(default)]
+    pub apply_mode: GitApplyMode,
     pub delta: DeltaConfig,
     // Git settings (guarded)
     pub merge_conflictstyle: String,
@@ -386,6 +432,11 @@ pub struct GitConfig {
     pub core_editor: String,
     pub help_autocorrect: u32,
     pub aliases: Vec<GitAlias>,
+    /// Per-directory identities, included via `includeIf "gitdir:..."`.
+    #[serde(default)]
+    pub identities: Vec<GitIdentity>,
+    #[serde(default)]
+    pub signing: GitSigningConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -410,6 +461,84 @@ pub struct DeltaConfig {
     pub wrap_max_lines: u32,
     pub true_color: String,
     pub hyperlinks: bool,
+    /// Compute the styles below from the palette instead of using the raw strings.
+    #[serde(default)]
+    pub from_palette: bool,
+    /// Explicit styles that win over the palette-derived ones; empty = derived.
+    #[serde(default)]
+    pub overrides: DeltaStyleOverrides,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeltaStyleOverrides {
+    pub file_style: String,
+    pub file_decoration_style: String,
+    pub hunk_header_style: String,
+    pub hunk_header_decoration_style: String,
+    pub minus_style: String,
+    pub plus_style: String,
+    pub minus_emph_style: String,
+    pub plus_emph_style: String,
+    pub zero_style: String,
+    pub whitespace_error_style: String,
+}
+
+/// The delta styles actually written to git config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeltaStyles {
+    pub file_style: String,
+    pub file_decoration_style: String,
+    pub hunk_header_style: String,
+    pub hunk_header_decoration_style: String,
+    pub minus_style: String,
+    pub plus_style: String,
+    pub minus_emph_style: String,
+    pub plus_emph_style: String,
+    pub zero_style: String,
+    pub whitespace_error_style: String,
+}
+
+impl DeltaConfig {
+    pub fn resolved_styles(&self, palette: &AyuPalette) -> DeltaStyles {
+        if !self.from_palette {
+            return DeltaStyles {
+                file_style: self.file_style.clone(),
+                file_decoration_style: self.file_decoration_style.clone(),
+                hunk_header_style: self.hunk_header_style.clone(),
+                hunk_header_decoration_style: self.hunk_header_decoration_style.clone(),
+                minus_style: self.minus_style.clone(),
+                plus_style: self.plus_style.clone(),
+                minus_emph_style: self.minus_emph_style.clone(),
+                plus_emph_style: self.plus_emph_style.clone(),
+                zero_style: self.zero_style.clone(),
+                whitespace_error_style: self.whitespace_error_style.clone(),
+            };
+        }
+
+        // Diff backgrounds are the accent blended into the background; falls
+        // back to the raw style if the palette has an invalid color.
+        let background = |accent: &str, amount: f32, fallback: &str| {
+            blend(&palette.bg, accent, amount)
+                .map(|bg| format!("syntax {}", bg))
+                .unwrap_or_else(|| fallback.to_string())
+        };
+        let pick = |explicit: &String, derived: String| {
+            if explicit.trim().is_empty() { derived } else { explicit.clone() }
+        };
+        let o = &self.overrides;
+        DeltaStyles {
+            file_style: pick(&o.file_style, format!("bold {}", palette.yellow)),
+            file_decoration_style: pick(&o.file_decoration_style, format!("{} ul", palette.yellow)),
+            hunk_header_style: pick(&o.hunk_header_style, "syntax bold".into()),
+            hunk_header_decoration_style: pick(&o.hunk_header_decoration_style, format!("{} box", palette.blue)),
+            minus_style: pick(&o.minus_style, background(&palette.red, 0.15, &self.minus_style)),
+            plus_style: pick(&o.plus_style, background(&palette.green, 0.12, &self.plus_style)),
+            minus_emph_style: pick(&o.minus_emph_style, background(&palette.red, 0.3, &self.minus_emph_style)),
+            plus_emph_style: pick(&o.plus_emph_style, background(&palette.green, 0.25, &self.plus_emph_style)),
+            zero_style: pick(&o.zero_style, "syntax".into()),
+            whitespace_error_style: pick(&o.whitespace_error_style, format!("reverse {}", palette.red)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -419,6 +548,191 @@ pub struct GitAlias {
     pub enabled: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitIdentity {
+    /// Short name used for the include file, e.g. "work".
+    pub id: String,
+    pub enabled: bool,
+    pub user_name: String,
+    pub email: String,
+    /// `user.signingkey`; empty to leave it unset.
+    #[serde(default)]
+    pub signing_key: String,
+    /// `gitdir:` patterns such as `~/work/`.
+    pub directories: Vec<String>,
+}
+
+impl GitIdentity {
+    /// Ids end up in file names, so only `[A-Za-z0-9_-]` is accepted.
+    pub fn has_valid_id(&self) -> bool {
+        !self.id.is_empty() && self.id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+
+    /// Home-relative path of this identity's include file.
+    pub fn include_file(&self) -> String {
+        format!(".config/git/ghostty-ultimate-{}.gitconfig", self.id)
+    }
+
+    pub fn settings(&self) -> Vec<(String, String)> {
+        let mut settings = vec![
+            ("user.name".to_string(), self.user_name.clone()),
+            ("user.email".to_string(), self.email.clone()),
+        ];
+        if !self.signing_key.is_empty() {
+            settings.push(("user.signingkey".into(), self.signing_key.clone()));
+        }
+        settings
+    }
+
+    /// A plain directory gets a trailing slash so git matches everything below it.
+    pub fn gitdir_patterns(&self) -> Vec<String> {
+        self.directories
+            .iter()
+            .map(|d| d.trim())
+            .filter(|d| !d.is_empty())
+            .map(|d| if d.ends_with('/') || d.contains('*') { d.to_string() } else { format!("{}/", d) })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SigningFormat {
+    #[default]
+    Ssh,
+    Openpgp,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitSigningConfig {
+    pub enabled: bool,
+    pub format: SigningFormat,
+    /// SSH: path to the key (or `key::<public key>`). OpenPGP: key id or fingerprint.
+    pub key: String,
+    pub sign_commits: bool,
+    pub sign_tags: bool,
+    /// Write `~/.config/git/allowed_signers` so `git log --show-signature` can verify SSH signatures.
+    pub allowed_signers: bool,
+    /// `gpg.ssh.program`, e.g. 1Password's `op-ssh-sign`; empty for ssh-keygen.
+    pub ssh_program: String,
+}
+
+impl Default for GitSigningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            format: SigningFormat::Ssh,
+            key: "~/.ssh/id_ed25519.pub".into(),
+            sign_commits: true,
+            sign_tags: true,
+            allowed_signers: true,
+            ssh_program: String::new(),
+        }
+    }
+}
+
+impl GitSigningConfig {
+    /// Home-relative path of the generated allowed-signers file.
+    pub const ALLOWED_SIGNERS_FILE: &'static str = ".config/git/allowed_signers";
+
+    /// Signing keys for the managed gitconfig; empty when signing is off.
+    pub fn settings(&self) -> Vec<(String, String)> {
+        if !self.enabled {
+            return vec![];
+        }
+        let ssh = self.format == SigningFormat::Ssh;
+        let mut settings = vec![
+            ("gpg.format".to_string(), if ssh { "ssh" } else { "openpgp" }.to_string()),
+            ("user.signingkey".to_string(), self.key.clone()),
+            ("commit.gpgsign".to_string(), self.sign_commits.to_string()),
+            ("tag.gpgsign".to_string(), self.sign_tags.to_string()),
+        ];
+        if ssh && !self.ssh_program.is_empty() {
+            settings.push(("gpg.ssh.program".into(), self.ssh_program.clone()));
+        }
+        if ssh && self.allowed_signers {
+            settings.push(("gpg.ssh.allowedSignersFile".into(), format!("~/{}", Self::ALLOWED_SIGNERS_FILE)));
+        }
+        settings
+    }
+}
+
+/// Settings live in `~/.config/git/ghostty-ultimate.gitconfig`, pulled in by
+/// one `include.path` entry in the global gitconfig.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GitApplyMode {
+    /// Include is placed last, so managed values win.
+    Override,
+    /// Keys the user already set are left out of the managed file.
+    #[default]
+    OnlyIfUnset,
+    /// Drop the include and delete the managed file.
+    Remove,
+}
+
+impl GitConfig {
+    /// Every key/value written to the managed gitconfig, in file order. The
+    /// `includeIf` entries come after the signing keys, so an identity's own
+    /// `user.signingkey` overrides the global one in its directories.
+    pub fn managed_settings(&self, palette: &AyuPalette) -> Vec<(String, String)> {
+        let mut settings = self.settings(palette);
+        settings.extend(self.signing.settings());
+        settings.extend(self.identity_includes());
+        settings
+    }
+
+    /// Delta and git behaviour settings, without signing or identities.
+    pub fn settings(&self, palette: &AyuPalette) -> Vec<(String, String)> {
+        let delta = &self.delta;
+        let styles = delta.resolved_styles(palette);
+        let mut settings: Vec<(String, String)> = vec![
+            ("core.pager".into(), "delta".into()),
+            ("interactive.diffFilter".into(), "delta --color-only".into()),
+            ("delta.navigate".into(), delta.navigate.to_string()),
+            ("delta.dark".into(), delta.dark.to_string()),
+            ("delta.line-numbers".into(), delta.line_numbers.to_string()),
+            ("delta.side-by-side".into(), delta.side_by_side.to_string()),
+            ("delta.syntax-theme".into(), delta.syntax_theme.clone()),
+            ("delta.file-style".into(), styles.file_style),
+            ("delta.file-decoration-style".into(), styles.file_decoration_style),
+            ("delta.hunk-header-style".into(), styles.hunk_header_style),
+            ("delta.hunk-header-decoration-style".into(), styles.hunk_header_decoration_style),
+            ("delta.minus-style".into(), styles.minus_style),
+            ("delta.plus-style".into(), styles.plus_style),
+            ("delta.minus-emph-style".into(), styles.minus_emph_style),
+            ("delta.plus-emph-style".into(), styles.plus_emph_style),
+            ("delta.zero-style".into(), styles.zero_style),
+            ("delta.whitespace-error-style".into(), styles.whitespace_error_style),
+            ("merge.conflictstyle".into(), self.merge_conflictstyle.clone()),
+            ("diff.algorithm".into(), self.diff_algorithm.clone()),
+            ("diff.colorMoved".into(), self.diff_color_moved.clone()),
+            ("pull.rebase".into(), self.pull_rebase.to_string()),
+            ("rebase.autostash".into(), self.rebase_autostash.to_string()),
+            ("push.autoSetupRemote".into(), self.push_auto_setup_remote.to_string()),
+            ("push.default".into(), self.push_default.clone()),
+            ("init.defaultBranch".into(), self.init_default_branch.clone()),
+            ("core.editor".into(), self.core_editor.clone()),
+            ("help.autocorrect".into(), self.help_autocorrect.to_string()),
+        ];
+        for alias in self.aliases.iter().filter(|a| a.enabled) {
+            settings.push((format!("alias.{}", alias.name), alias.command.clone()));
+        }
+        settings
+    }
+
+    /// One `includeIf.gitdir:<pattern>.path` entry per identity directory.
+    pub fn identity_includes(&self) -> Vec<(String, String)> {
+        let mut includes = Vec::new();
+        for identity in self.identities.iter().filter(|i| i.enabled && i.has_valid_id()) {
+            for pattern in identity.gitdir_patterns() {
+                includes.push((format!("includeIf.gitdir:{}.path", pattern), format!("~/{}", identity.include_file())));
+            }
+        }
+        includes
+    }
+}
+
 // ── Section 11: Themes ──────────────────────────────────────────────
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThemesConfig {
@@ -499,3 +813,79 @@ pub struct FunctionEntry {
     pub description: String,
     pub enabled: bool,
 }
+
+// ── Custom sections ─────────────────────────────────────────────────
+
+/// State of a manifest-defined section, keyed by its id in `custom_sections`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CustomSectionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Values for the manifest's settings schema; missing keys take the
+    /// schema defaults.
+    #[serde(default)]
+    pub settings: serde_json::Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity(id: &str, directories: &[&str]) -> GitIdentity {
+        GitIdentity {
+            id: id.into(),
+            enabled: true,
+            user_name: "Me".into(),
+            email: "me@work.example".into(),
+            signing_key: String::new(),
+            directories: directories.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn identity_directories_match_everything_below_them() {
+        let work = identity("work", &["~/work", "~/clients/", "~/src/*/acme", "  "]);
+        assert_eq!(work.gitdir_patterns(), ["~/work/", "~/clients/", "~/src/*/acme"]);
+        assert_eq!(work.include_file(), ".config/git/ghostty-ultimate-work.gitconfig");
+        assert!(!work.settings().iter().any(|(key, _)| key == "user.signingkey"));
+        for id in ["", "../x", "my work"] {
+            assert!(!identity(id, &[]).has_valid_id(), "{:?}", id);
+        }
+    }
+
+    #[test]
+    fn only_enabled_valid_identities_are_included() {
+        let mut git = crate::config::schema::GhosttyConfig::default().git;
+        let mut off = identity("off", &["~/off"]);
+        off.enabled = false;
+        git.identities = vec![identity("work", &["~/work"]), off, identity("bad id", &["~/bad"])];
+        assert_eq!(
+            git.identity_includes(),
+            [("includeIf.gitdir:~/work/.path".to_string(), "~/.config/git/ghostty-ultimate-work.gitconfig".to_string())]
+        );
+    }
+
+    #[test]
+    fn delta_styles_follow_the_palette_unless_overridden() {
+        let config = crate::config::schema::GhosttyConfig::default();
+        let (mut delta, mut palette) = (config.git.delta, config.palette);
+        delta.from_palette = false;
+        assert_eq!(delta.resolved_styles(&palette).minus_style, delta.minus_style);
+
+        delta.from_palette = true;
+        palette.yellow = "#e6b450".into();
+        palette.bg = "#000000".into();
+        palette.red = "#ff0000".into();
+        let styles = delta.resolved_styles(&palette);
+        assert_eq!(styles.file_style, "bold #e6b450");
+        assert_eq!(styles.minus_style, "syntax #260000");
+        assert_eq!(styles.minus_emph_style, "syntax #4d0000");
+
+        delta.overrides.file_style = "bold red".into();
+        palette.red = "crimson".into();
+        let styles = delta.resolved_styles(&palette);
+        assert_eq!(styles.file_style, "bold red");
+        // A color that cannot be blended keeps the raw style
+        assert_eq!(styles.minus_style, delta.minus_style);
+    }
+}
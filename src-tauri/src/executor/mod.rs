@@ -1,2 +1,4 @@
 pub mod runner;
 pub mod parser;
+pub mod sandbox;
+pub mod reload;
@@ -1,4 +1,6 @@
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionEvent {
@@ -16,16 +18,13 @@ pub fn parse_line(line: &str) -> Option<ExecutionEvent> {
         .unwrap_or_default()
         .as_secs();
 
-    // Detect section headers like "1/13 —" or "2/13 —"
-    if let Some(caps) = regex::Regex::new(r"(\d+)/13\s*[—–-]")
-        .ok()?
-        .captures(line)
-    {
-        let section: u32 = caps.get(1)?.as_str().parse().ok()?;
+    // Detect section headers like "[1/13] — Git"; the total is however many
+    // sections the script was generated with
+    if let Some((section, total)) = section_header(line) {
         return Some(ExecutionEvent {
             event_type: "progress".into(),
             section: Some(section),
-            total_sections: Some(13),
+            total_sections: Some(total),
             message: line.to_string(),
             timestamp: ts,
         });
@@ -35,8 +34,8 @@ pub fn parse_line(line: &str) -> Option<ExecutionEvent> {
     if line.contains("GHOSTTY ULTIMATE — Ayu Dark") && line.contains("✓") {
         return Some(ExecutionEvent {
             event_type: "complete".into(),
-            section: Some(13),
-            total_sections: Some(13),
+            section: None,
+            total_sections: None,
             message: line.to_string(),
             timestamp: ts,
         });
@@ -55,3 +54,37 @@ pub fn parse_line(line: &str) -> Option<ExecutionEvent> {
 
     None
 }
+
+/// Matches the text `print_header` prints for a numbered section, once its
+/// colour codes and indent are removed, so tool output such as
+/// "Receiving objects: 3/10 - done" is not taken for a section.
+fn section_header(line: &str) -> Option<(u32, u32)> {
+    static ANSI: OnceLock<Regex> = OnceLock::new();
+    static HEADER: OnceLock<Regex> = OnceLock::new();
+    let ansi = ANSI.get_or_init(|| Regex::new(r"\x1b\[[0-9;]*m").unwrap());
+    let header = HEADER.get_or_init(|| Regex::new(r"^\[(\d+)/(\d+)\] — ").unwrap());
+    let text = ansi.replace_all(line, "");
+    let caps = header.captures(text.trim_start())?;
+    Some((caps[1].parse().ok()?, caps[2].parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_section_headers_report_progress() {
+        let header = "  \x1b[38;2;205;161;250m\x1b[1m[3/13] — 🔀 Git\x1b[0m";
+        let event = parse_line(header).unwrap();
+        assert_eq!(event.event_type, "progress");
+        assert_eq!((event.section, event.total_sections), (Some(3), Some(13)));
+
+        for line in [
+            "Receiving objects: 45/120 - done",
+            "==> Downloading 1/3 — bottle",
+            "  \x1b[1mFetching 2/5 - ripgrep\x1b[0m",
+        ] {
+            assert!(parse_line(line).is_none(), "{line}");
+        }
+    }
+}
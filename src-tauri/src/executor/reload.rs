@@ -0,0 +1,248 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Reload hooks that can be run after a section is applied.
+pub const HOOKS: [&str; 5] = ["ghostty", "neovim", "tmux", "bat", "yazi"];
+
+/// Outcome of one reload hook across every running instance it found.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReloadResult {
+    pub hook: String,
+    /// Instances found (pids or socket paths).
+    pub instances: Vec<String>,
+    pub reloaded: Vec<String>,
+    /// Instances that only pick up the new config after a restart.
+    pub restart_needed: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+impl ReloadResult {
+    fn new(hook: &str) -> Self {
+        Self { hook: hook.to_string(), instances: vec![], reloaded: vec![], restart_needed: vec![], errors: vec![] }
+    }
+
+    pub fn summary(&self) -> String {
+        let mut line = if self.instances.is_empty() {
+            format!("{}: no running instances", self.hook)
+        } else {
+            format!("{}: reloaded {}/{}", self.hook, self.reloaded.len(), self.instances.len())
+        };
+        if !self.restart_needed.is_empty() {
+            line.push_str(&format!(", restart {} to load the new config", self.restart_needed.len()));
+        }
+        if !self.errors.is_empty() {
+            line.push_str(&format!(" ({})", self.errors.join("; ")));
+        }
+        line
+    }
+
+    /// Run `cmd` for `instance`, recording success or the error.
+    fn run(&mut self, instance: &str, cmd: &mut Command) {
+        self.instances.push(instance.to_string());
+        match cmd.output() {
+            Ok(out) if out.status.success() => self.reloaded.push(instance.to_string()),
+            Ok(out) => self.errors.push(format!(
+                "{}: {}",
+                instance,
+                String::from_utf8_lossy(&out.stderr).trim()
+            )),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                self.instances.pop();
+                self.errors.push(format!("{} is not installed", self.hook));
+            }
+            Err(e) => self.errors.push(format!("{}: {}", instance, e)),
+        }
+    }
+}
+
+pub fn run_hook(hook: &str, home: &Path) -> Result<ReloadResult, String> {
+    match hook {
+        "ghostty" => Ok(reload_ghostty()),
+        "neovim" => Ok(reload_neovim()),
+        "tmux" => Ok(reload_tmux(&home.join(".tmux.conf"))),
+        "bat" => Ok(rebuild_bat_cache()),
+        "yazi" => Ok(clear_yazi_cache()),
+        _ => Err(format!("Unknown reload hook '{}'", hook)),
+    }
+}
+
+/// Run a custom section's reload command, reported under `hook`.
+pub fn run_command(hook: &str, command: &[String]) -> ReloadResult {
+    let mut result = ReloadResult::new(hook);
+    if let Some((program, args)) = command.split_first() {
+        result.run(&command.join(" "), Command::new(program).args(args));
+    }
+    result
+}
+
+// ── Ghostty ─────────────────────────────────────────────────────────
+
+/// Ghostty reloads its configuration on SIGUSR2.
+fn reload_ghostty() -> ReloadResult {
+    let mut result = ReloadResult::new("ghostty");
+    for pid in pgrep("ghostty") {
+        result.run(&format!("pid {}", pid), Command::new("kill").args(["-USR2", &pid]));
+    }
+    result
+}
+
+fn pgrep(name: &str) -> Vec<String> {
+    Command::new("pgrep")
+        .args(["-x", name])
+        .output()
+        .ok()
+        .map(|o| String::from_utf8_lossy(&o.stdout).lines().map(|l| l.trim().to_string()).collect())
+        .unwrap_or_default()
+}
+
+// ── Neovim ──────────────────────────────────────────────────────────
+
+/// Sources `$MYVIMRC` in a server and prints "ok", or the error it raised.
+const NEOVIM_SOURCE_EXPR: &str = "luaeval(\"select(2, pcall(vim.cmd.source, vim.env.MYVIMRC)) or 'ok'\")";
+
+/// Ask every running server to source its init.lua again over its RPC
+/// socket. An instance that cannot (an init.lua that errors when sourced twice,
+/// a stale socket, no nvim on PATH) is listed as needing a restart.
+fn reload_neovim() -> ReloadResult {
+    let mut result = ReloadResult::new("neovim");
+    for socket in neovim_sockets() {
+        let socket = socket.to_string_lossy().to_string();
+        let output = Command::new("nvim").args(["--server", &socket, "--remote-expr", NEOVIM_SOURCE_EXPR]).output();
+        record_neovim_reload(&mut result, socket, output);
+    }
+    result
+}
+
+fn record_neovim_reload(result: &mut ReloadResult, socket: String, output: std::io::Result<Output>) {
+    result.instances.push(socket.clone());
+    let error = match output {
+        Ok(out) if out.status.success() && String::from_utf8_lossy(&out.stdout).trim() == "ok" => {
+            result.reloaded.push(socket);
+            return;
+        }
+        Ok(out) => {
+            let stdout = String::from_utf8_lossy(&out.stdout).trim().to_string();
+            if stdout.is_empty() { String::from_utf8_lossy(&out.stderr).trim().to_string() } else { stdout }
+        }
+        Err(e) => e.to_string(),
+    };
+    result.errors.push(format!("{}: {}", socket, error));
+    result.restart_needed.push(socket);
+}
+
+/// Default server sockets: `$XDG_RUNTIME_DIR/nvim.<pid>.0` on Linux,
+/// `$TMPDIR/nvim.$USER/<id>/nvim.<pid>.0` on macOS.
+fn neovim_sockets() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Ok(runtime) = std::env::var("XDG_RUNTIME_DIR") {
+        dirs.push(PathBuf::from(runtime));
+    }
+    let tmp = std::env::temp_dir();
+    let user = std::env::var("USER").unwrap_or_default();
+    let user_dir = tmp.join(format!("nvim.{}", user));
+    dirs.push(user_dir.clone());
+    if let Ok(entries) = fs::read_dir(&user_dir) {
+        dirs.extend(entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()));
+    }
+
+    let mut sockets: Vec<PathBuf> = dirs
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name().is_some_and(|n| n.to_string_lossy().starts_with("nvim.")) && is_socket(p)
+        })
+        .collect();
+    sockets.sort();
+    sockets
+}
+
+// ── tmux ────────────────────────────────────────────────────────────
+
+fn reload_tmux(conf: &Path) -> ReloadResult {
+    let mut result = ReloadResult::new("tmux");
+    let conf = conf.to_string_lossy().to_string();
+    for socket in tmux_sockets() {
+        let socket = socket.to_string_lossy().to_string();
+        result.run(&socket, Command::new("tmux").args(["-S", &socket, "source-file", &conf]));
+    }
+    result
+}
+
+/// Every server socket in `${TMUX_TMPDIR:-/tmp}/tmux-<uid>/`, not just `default`.
+fn tmux_sockets() -> Vec<PathBuf> {
+    let uid = Command::new("id")
+        .arg("-u")
+        .output()
+        .ok()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_default();
+    let base = std::env::var("TMUX_TMPDIR").map(PathBuf::from).unwrap_or_else(|_| PathBuf::from("/tmp"));
+    let dir = base.join(format!("tmux-{}", uid));
+
+    let mut sockets: Vec<PathBuf> = fs::read_dir(&dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).filter(|p| is_socket(p)).collect())
+        .unwrap_or_default();
+    sockets.sort();
+    sockets
+}
+
+// ── bat / yazi ──────────────────────────────────────────────────────
+
+fn rebuild_bat_cache() -> ReloadResult {
+    let mut result = ReloadResult::new("bat");
+    result.run("bat cache --build", Command::new("bat").args(["cache", "--build"]));
+    result
+}
+
+fn clear_yazi_cache() -> ReloadResult {
+    let mut result = ReloadResult::new("yazi");
+    result.run("yazi --clear-cache", Command::new("yazi").arg("--clear-cache"));
+    result
+}
+
+#[cfg(unix)]
+fn is_socket(path: &Path) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+    fs::metadata(path).map(|m| m.file_type().is_socket()).unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_socket(_path: &Path) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    fn output(code: i32, stdout: &str, stderr: &str) -> std::io::Result<Output> {
+        use std::os::unix::process::ExitStatusExt;
+        Ok(Output { status: ExitStatusExt::from_raw(code << 8), stdout: stdout.into(), stderr: stderr.into() })
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn neovim_instances_that_fail_to_source_need_a_restart() {
+        let mut result = ReloadResult::new("neovim");
+        record_neovim_reload(&mut result, "/run/nvim.1.0".into(), output(0, "ok\n", ""));
+        record_neovim_reload(&mut result, "/run/nvim.2.0".into(), output(0, "Vim:E484: Can't open file /nope/init.lua\n", ""));
+        record_neovim_reload(&mut result, "/run/nvim.3.0".into(), output(1, "", "connection refused"));
+        assert_eq!(result.reloaded, ["/run/nvim.1.0"]);
+        assert_eq!(result.restart_needed, ["/run/nvim.2.0", "/run/nvim.3.0"]);
+        assert_eq!(result.errors[1], "/run/nvim.3.0: connection refused");
+        assert!(result.summary().starts_with("neovim: reloaded 1/3, restart 2 to load the new config ("));
+    }
+
+    #[test]
+    fn summary_counts_reloaded_instances() {
+        let mut result = ReloadResult::new("tmux");
+        assert_eq!(result.summary(), "tmux: no running instances");
+        result.instances.push("/tmp/tmux-0/default".into());
+        result.reloaded.push("/tmp/tmux-0/default".into());
+        assert_eq!(result.summary(), "tmux: reloaded 1/1");
+    }
+}
@@ -0,0 +1,369 @@
+use crate::config::schema::GhosttyConfig;
+use crate::generator::applier::{apply_section, ApplyTarget};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Commands replaced by logging stubs inside the sandbox: package managers,
+/// downloads (so `curl ... | sh` runs nothing) and login-shell changes.
+const STUBBED_COMMANDS: [&str; 6] = ["brew", "mas", "port", "curl", "wget", "chsh"];
+
+/// git subcommands that reach the network. The git stub logs these (creating
+/// the clone's target directory) and hands everything else to the real git.
+const GIT_NETWORK_COMMANDS: [&str; 6] = ["clone", "fetch", "pull", "push", "ls-remote", "submodule"];
+
+/// Environment variables that could point the script back at the real home.
+const CLEARED_ENV: [&str; 8] = [
+    "XDG_CONFIG_HOME",
+    "XDG_DATA_HOME",
+    "XDG_STATE_HOME",
+    "XDG_CACHE_HOME",
+    "ZDOTDIR",
+    "TMUX",
+    "TMUX_TMPDIR",
+    "GIT_CONFIG_GLOBAL",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SandboxOptions {
+    /// Home-relative paths copied from the real home before the run (e.g. ".zshrc").
+    #[serde(default)]
+    pub seed_paths: Vec<String>,
+    /// Keep the sandbox directory instead of deleting it after the diff.
+    #[serde(default)]
+    pub keep: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SandboxReport {
+    /// Sandbox root, only set when `keep` was requested.
+    pub root: Option<String>,
+    pub exit_code: Option<i32>,
+    pub created: Vec<String>,
+    pub modified: Vec<String>,
+    pub deleted: Vec<String>,
+    /// Invocations caught by the stubs.
+    pub stubbed_calls: Vec<String>,
+    pub log: Vec<String>,
+}
+
+/// A throwaway HOME with stubbed package managers. The directory is deleted
+/// when the sandbox is dropped, unless `keep` was requested.
+pub struct Sandbox {
+    pub root: PathBuf,
+    pub home: PathBuf,
+    bin: PathBuf,
+    keep: bool,
+}
+
+impl Sandbox {
+    pub fn create(options: &SandboxOptions) -> Result<Self, String> {
+        Self::create_in(&std::env::temp_dir(), options)
+    }
+
+    fn create_in(base: &Path, options: &SandboxOptions) -> Result<Self, String> {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let root = base.join(format!("ghostty-ultimate-sandbox-{}-{}", std::process::id(), nanos));
+        let home = root.join("home");
+        let bin = root.join("bin");
+        // Not kept until it is set up: a failed setup never reaches the report
+        let mut sandbox = Self { root, home, bin, keep: false };
+        fs::create_dir_all(&sandbox.home).map_err(|e| format!("Cannot create sandbox home: {}", e))?;
+        fs::create_dir_all(&sandbox.bin).map_err(|e| format!("Cannot create sandbox bin: {}", e))?;
+        sandbox.write_stubs()?;
+        sandbox.seed(&options.seed_paths)?;
+        sandbox.keep = options.keep;
+        Ok(sandbox)
+    }
+
+    fn write_stubs(&self) -> Result<(), String> {
+        let log = self.root.join("stub-calls.log");
+        for name in STUBBED_COMMANDS {
+            // `list` reports nothing installed so install paths are exercised
+            let script = format!(
+                "#!/bin/sh\necho \"{} $*\" >> '{}'\n[ \"$1\" = \"list\" ] && exit 1\nexit 0\n",
+                name,
+                log.display()
+            );
+            self.write_stub(name, &script)?;
+        }
+
+        let real_git = find_in_path("git").map(|p| p.display().to_string()).unwrap_or_else(|| "false".into());
+        let script = format!(
+            r#"#!/bin/sh
+case "$1" in
+  {commands})
+    echo "git $*" >> '{log}'
+    # A clone's last argument is its target directory
+    if [ "$1" = clone ]; then
+      for last; do :; done
+      case "$last" in *://*|*@*:*) ;; *) mkdir -p "$last" ;; esac
+    fi
+    exit 0 ;;
+esac
+exec '{git}' "$@"
+"#,
+            commands = GIT_NETWORK_COMMANDS.join("|"),
+            log = log.display(),
+            git = real_git
+        );
+        self.write_stub("git", &script)
+    }
+
+    fn write_stub(&self, name: &str, script: &str) -> Result<(), String> {
+        let path = self.bin.join(name);
+        fs::write(&path, script).map_err(|e| format!("Cannot write {} stub: {}", name, e))?;
+        make_executable(&path)
+    }
+
+    fn seed(&self, seed_paths: &[String]) -> Result<(), String> {
+        let real_home = dirs::home_dir().ok_or("Cannot find home directory")?;
+        for rel in seed_paths {
+            let rel = rel.trim_start_matches("~/").trim_start_matches('/');
+            if rel.split('/').any(|part| part == "..") {
+                return Err(format!("Seed path escapes home: {}", rel));
+            }
+            let src = real_home.join(rel);
+            if !src.exists() {
+                continue;
+            }
+            copy_recursive(&src, &self.home.join(rel))?;
+        }
+        Ok(())
+    }
+
+    /// Build a command that runs with HOME and PATH pointed into the sandbox.
+    pub fn command(&self, program: &str) -> Command {
+        let path = std::env::var("PATH").unwrap_or_default();
+        let mut cmd = Command::new(program);
+        cmd.env("HOME", &self.home)
+            .env("PATH", format!("{}:{}", self.bin.display(), path))
+            .current_dir(&self.home);
+        for var in CLEARED_ENV {
+            cmd.env_remove(var);
+        }
+        cmd
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<String, u64> {
+        let mut files = BTreeMap::new();
+        walk(&self.home, &self.home, &mut files);
+        files
+    }
+
+    fn stubbed_calls(&self) -> Vec<String> {
+        fs::read_to_string(self.root.join("stub-calls.log"))
+            .map(|s| s.lines().map(|l| l.to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn finish(
+        self,
+        before: &BTreeMap<String, u64>,
+        exit_code: Option<i32>,
+        log: Vec<String>,
+    ) -> SandboxReport {
+        let after = self.snapshot();
+        let created = after.keys().filter(|k| !before.contains_key(*k)).cloned().collect();
+        let modified = after
+            .iter()
+            .filter(|(k, hash)| before.get(*k).is_some_and(|old| old != *hash))
+            .map(|(k, _)| k.clone())
+            .collect();
+        let deleted = before.keys().filter(|k| !after.contains_key(*k)).cloned().collect();
+        let stubbed_calls = self.stubbed_calls();
+
+        let root = self.keep.then(|| self.root.to_string_lossy().to_string());
+
+        SandboxReport { root, exit_code, created, modified, deleted, stubbed_calls, log }
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        if !self.keep {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+}
+
+/// Run a generated setup script against a throwaway HOME and report what it touched.
+pub fn run_script(script: &str, args: &[String], options: &SandboxOptions) -> Result<SandboxReport, String> {
+    let sandbox = Sandbox::create(options)?;
+    let script_path = sandbox.root.join("setup.sh");
+    fs::write(&script_path, script).map_err(|e| format!("Cannot write sandbox script: {}", e))?;
+
+    let before = sandbox.snapshot();
+    let output = sandbox
+        .command("bash")
+        .arg(&script_path)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to spawn script: {}", e))?;
+
+    let log = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .chain(String::from_utf8_lossy(&output.stderr).lines())
+        .map(|l| l.to_string())
+        .collect();
+
+    Ok(sandbox.finish(&before, output.status.code(), log))
+}
+
+/// Run the applier for the given sections with the sandbox home as its root.
+pub fn run_applier(config: &GhosttyConfig, sections: &[String], options: &SandboxOptions) -> Result<SandboxReport, String> {
+    let sandbox = Sandbox::create(options)?;
+    let target = ApplyTarget { root: Some(sandbox.home.to_string_lossy().to_string()) };
+
+    let before = sandbox.snapshot();
+    let mut log = Vec::new();
+    let mut failed = false;
+    for section in sections {
+        match apply_section(config, section, &target) {
+            Ok(message) => log.push(format!("{}: {}", section, message)),
+            Err(e) => {
+                failed = true;
+                log.push(format!("{}: ✗ {}", section, e));
+            }
+        }
+    }
+
+    Ok(sandbox.finish(&before, Some(if failed { 1 } else { 0 }), log))
+}
+
+fn walk(base: &Path, dir: &Path, files: &mut BTreeMap<String, u64>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(meta) = fs::symlink_metadata(&path) else { continue };
+        let rel = path.strip_prefix(base).unwrap_or(&path).to_string_lossy().to_string();
+        if meta.is_dir() {
+            walk(base, &path, files);
+        } else if meta.file_type().is_symlink() {
+            let target = fs::read_link(&path).unwrap_or_default();
+            files.insert(rel, hash_bytes(target.to_string_lossy().as_bytes()));
+        } else {
+            let content = fs::read(&path).unwrap_or_default();
+            files.insert(rel, hash_bytes(&content));
+        }
+    }
+}
+
+/// First `name` on the real PATH, for stubs that pass calls through.
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).map(|dir| dir.join(name)).find(|p| p.is_file())
+}
+
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn copy_recursive(src: &Path, dst: &Path) -> Result<(), String> {
+    if src.is_dir() {
+        fs::create_dir_all(dst).map_err(|e| format!("Cannot create {}: {}", dst.display(), e))?;
+        let entries = fs::read_dir(src).map_err(|e| format!("Cannot read {}: {}", src.display(), e))?;
+        for entry in entries.flatten() {
+            copy_recursive(&entry.path(), &dst.join(entry.file_name()))?;
+        }
+    } else {
+        if let Some(parent) = dst.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Cannot create {}: {}", parent.display(), e))?;
+        }
+        fs::copy(src, dst).map_err(|e| format!("Cannot copy {}: {}", src.display(), e))?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+pub(crate) fn make_executable(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+        .map_err(|e| format!("Cannot chmod {}: {}", path.display(), e))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn make_executable(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base(test: &str) -> PathBuf {
+        let base = std::env::temp_dir().join(format!("ghostty-ultimate-sandbox-test-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        base
+    }
+
+    fn entries(dir: &Path) -> usize {
+        fs::read_dir(dir).unwrap().count()
+    }
+
+    #[test]
+    fn sandbox_is_removed_unless_kept() {
+        let base = base("cleanup");
+
+        let escaping = SandboxOptions { seed_paths: vec!["../etc".into()], keep: true };
+        assert!(Sandbox::create_in(&base, &escaping).is_err());
+        assert_eq!(entries(&base), 0, "a failed setup leaves nothing behind");
+
+        let sandbox = Sandbox::create_in(&base, &SandboxOptions::default()).unwrap();
+        let report = sandbox.finish(&BTreeMap::new(), Some(0), vec![]);
+        assert!(report.root.is_none());
+        assert_eq!(entries(&base), 0);
+
+        let kept = SandboxOptions { keep: true, ..Default::default() };
+        let report = Sandbox::create_in(&base, &kept).unwrap().finish(&BTreeMap::new(), Some(0), vec![]);
+        assert!(Path::new(&report.root.unwrap()).join("home").is_dir());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn network_and_system_commands_are_stubbed() {
+        let base = base("stubs");
+        let sandbox = Sandbox::create_in(&base, &SandboxOptions::default()).unwrap();
+        let script = r#"
+git clone --depth 1 https://github.com/tmux-plugins/tpm "$HOME/.tmux/plugins/tpm"
+[ -d "$HOME/.tmux/plugins/tpm" ] && echo cloned
+curl -fsSL https://example.com/install.sh | sh
+chsh -s /bin/zsh
+brew list ripgrep || echo "not installed"
+git config --global user.name Sandbox
+"#;
+        let home = sandbox.home.display().to_string();
+        let before = sandbox.snapshot();
+        let output = sandbox.command("bash").args(["-c", script]).output().unwrap();
+        let log = String::from_utf8_lossy(&output.stdout).lines().map(|l| l.to_string()).collect();
+        let report = sandbox.finish(&before, output.status.code(), log);
+
+        assert_eq!(
+            report.stubbed_calls,
+            [
+                format!("git clone --depth 1 https://github.com/tmux-plugins/tpm {}/.tmux/plugins/tpm", home),
+                "curl -fsSL https://example.com/install.sh".into(),
+                "chsh -s /bin/zsh".into(),
+                "brew list ripgrep".into(),
+            ]
+        );
+        assert_eq!(report.log, ["cloned", "not installed"]);
+        // Local git commands still run, against the sandbox home
+        if find_in_path("git").is_some() {
+            assert_eq!(report.created, [".gitconfig"]);
+        }
+        let _ = fs::remove_dir_all(&base);
+    }
+}
@@ -1,117 +1,211 @@
 use crate::config::schema::GhosttyConfig;
-use super::engine::render_apply_template;
+use crate::config::sections::{GhosttyLayout, GitApplyMode, GitSigningConfig, SigningFormat};
+use super::custom;
+use super::registry;
+use super::engine::{render_apply_template, render_checked, Syntax};
+use super::shaders;
+use super::signing;
+use super::transaction::Journal;
+use crate::executor::reload;
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
-/// Apply a specific section's config to disk.
-/// Returns a status message on success.
-pub fn apply_section(config: &GhosttyConfig, section: &str) -> Result<String, String> {
-    match section {
-        "ghostty" => apply_ghostty(config),
-        "starship" => apply_starship(config),
-        "tmux" => apply_tmux(config),
-        "neovim" => apply_neovim(config),
-        "tool_configs" => apply_tool_configs(config),
-        "git" => apply_git(config),
-        "themes" => apply_themes(config),
-        "fastfetch" => apply_fastfetch(config),
-        "zsh_plugins" => apply_zsh_plugins(config),
-        "zshrc" => apply_zshrc(config),
-        _ => Err(format!("Section '{}' is not live-applyable", section)),
+/// Where applied configs are written.
+///
+/// With no root the current user's home is used and git settings go to
+/// `--global`. With a root (a staging directory, a container rootfs, another
+/// user's home) every path is resolved under it, git settings are written to
+/// `<root>/.gitconfig`, and reload hooks that touch running processes are skipped.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApplyTarget {
+    #[serde(default)]
+    pub root: Option<String>,
+}
+
+impl ApplyTarget {
+    pub fn home_dir(&self) -> Result<PathBuf, String> {
+        match &self.root {
+            Some(root) => {
+                let root = PathBuf::from(root);
+                if !root.is_absolute() {
+                    return Err(format!("Apply root must be an absolute path: {}", root.display()));
+                }
+                fs::create_dir_all(&root)
+                    .map_err(|e| format!("Failed to create apply root {}: {}", root.display(), e))?;
+                Ok(root)
+            }
+            None => dirs::home_dir().ok_or_else(|| "Could not determine home directory".to_string()),
+        }
+    }
+
+    /// True when writing to the real home, so running tools may be signalled.
+    pub fn is_live(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// The file `git config` writes to for this target.
+    pub(crate) fn git_config_path(&self) -> Result<PathBuf, String> {
+        let home = self.home_dir()?;
+        let xdg = home.join(".config/git/config");
+        if self.is_live() && !home.join(".gitconfig").exists() && xdg.exists() {
+            return Ok(xdg);
+        }
+        Ok(home.join(".gitconfig"))
+    }
+
+    /// `git config` invocation scoped to this target.
+    fn git_config(&self) -> Result<Command, String> {
+        let mut cmd = Command::new("git");
+        cmd.arg("config");
+        if self.is_live() {
+            cmd.arg("--global");
+        } else {
+            cmd.arg("--file").arg(self.home_dir()?.join(".gitconfig"));
+        }
+        Ok(cmd)
     }
 }
 
-fn home_dir() -> Result<PathBuf, String> {
-    dirs::home_dir().ok_or_else(|| "Could not determine home directory".to_string())
+/// Apply a specific section's config to disk.
+/// Returns a status message on success; on failure any files already
+/// written for the section are restored. When writing to the real home the
+/// section's reload hooks run afterwards and are summarized in the message.
+pub fn apply_section(config: &GhosttyConfig, section: &str, target: &ApplyTarget) -> Result<String, String> {
+    let mut journal = Journal::default();
+    let message = apply_section_with(config, section, target, &mut journal).inspect_err(|_| {
+        journal.rollback();
+    })?;
+
+    let reloads = reload_hooks(section, target)?;
+    if reloads.is_empty() {
+        Ok(message)
+    } else {
+        Ok(format!("{} — {}", message, reloads.join(", ")))
+    }
 }
 
-fn ensure_parent(path: &PathBuf) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create directory {}: {}", parent.display(), e))?;
+/// Run the reload hooks for an applied section; nothing runs for alternate roots.
+pub fn reload_hooks(section: &str, target: &ApplyTarget) -> Result<Vec<String>, String> {
+    if !target.is_live() {
+        return Ok(Vec::new());
     }
-    Ok(())
+    let home = target.home_dir()?;
+    let results = match registry::builtin(section) {
+        Some(def) => def.reload_hooks.iter().filter_map(|hook| reload::run_hook(hook, &home).ok()).collect(),
+        None => custom::registry().get(section).map(|m| custom::run_reload_hooks(m, &home)).unwrap_or_default(),
+    };
+    Ok(results.iter().map(|r| r.summary()).collect())
+}
+
+/// Whether a section is switched on in the config.
+pub fn section_enabled(config: &GhosttyConfig, section: &str) -> bool {
+    registry::is_enabled(config, section)
 }
 
-fn write_config(path: &PathBuf, content: &str) -> Result<(), String> {
-    ensure_parent(path)?;
-    fs::write(path, content)
-        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+/// Apply a section, recording every write in `journal`.
+pub fn apply_section_with(
+    config: &GhosttyConfig,
+    section: &str,
+    target: &ApplyTarget,
+    journal: &mut Journal,
+) -> Result<String, String> {
+    if let Some(manifest) = custom::registry().get(section) {
+        return custom::apply(config, manifest, &target.home_dir()?, journal);
+    }
+    match registry::builtin(section).and_then(|def| def.apply) {
+        Some(apply) => apply(config, target, journal),
+        None => Err(format!("Section '{}' is not live-applyable", section)),
+    }
 }
 
 // ── Ghostty ─────────────────────────────────────────────────────────
 
-fn apply_ghostty(config: &GhosttyConfig) -> Result<String, String> {
-    let home = home_dir()?;
+/// Files included from the base config in the modular layout, in load
+/// order. Must match the `config-file` lines in apply/ghostty_modular.tera.
+pub(crate) const GHOSTTY_INCLUDES: [(&str, &str); 4] = [
+    ("fonts", "apply/ghostty_fonts.tera"),
+    ("appearance", "apply/ghostty_appearance.tera"),
+    ("keybinds", "apply/ghostty_keybinds.tera"),
+    ("extra", "apply/ghostty_extra.tera"),
+];
+
+pub(crate) fn apply_ghostty(config: &GhosttyConfig, target: &ApplyTarget, journal: &mut Journal) -> Result<String, String> {
+    let home = target.home_dir()?;
     let ghostty_dir = home.join(".config/ghostty");
+    shaders::validate_chain(config, &home)?;
 
-    // Write theme file
+    // Write theme file, named after the palette
     let theme_content = render_apply_template(config, "apply/ghostty_theme.tera")?;
-    let theme_path = ghostty_dir.join("themes/ayu-dark");
-    write_config(&theme_path, &theme_content)?;
+    let theme_path = ghostty_dir.join("themes").join(config.theme_name());
+    journal.write(&theme_path, &theme_content)?;
 
-    // Write config file
-    let config_content = render_apply_template(config, "apply/ghostty_config.tera")?;
     let config_path = ghostty_dir.join("config");
-    write_config(&config_path, &config_content)?;
+    if config.ghostty.layout == GhosttyLayout::Single {
+        let config_content = render_apply_template(config, "apply/ghostty_config.tera")?;
+        journal.write(&config_path, &config_content)?;
+        return Ok("Ghostty config applied".to_string());
+    }
 
-    // Ghostty auto-reloads its config - no post-hook needed
-    Ok("Ghostty config applied (auto-reloads)".to_string())
+    journal.write(&config_path, &render_apply_template(config, "apply/ghostty_modular.tera")?)?;
+    for (name, template) in GHOSTTY_INCLUDES {
+        journal.write(&ghostty_dir.join(name), &render_apply_template(config, template)?)?;
+    }
+    // The local overrides file belongs to the user once it exists
+    let local_path = ghostty_dir.join("local");
+    if !local_path.exists() {
+        journal.write(&local_path, &render_apply_template(config, "apply/ghostty_local.tera")?)?;
+    }
+    Ok("Ghostty config applied (modular layout)".to_string())
 }
 
 // ── Starship ────────────────────────────────────────────────────────
 
-fn apply_starship(config: &GhosttyConfig) -> Result<String, String> {
-    let home = home_dir()?;
-    let content = render_apply_template(config, "apply/starship_config.tera")?;
+pub(crate) fn apply_starship(config: &GhosttyConfig, target: &ApplyTarget, journal: &mut Journal) -> Result<String, String> {
+    let home = target.home_dir()?;
+    let content = render_checked(config, "apply/starship_config.tera", Syntax::Toml)?;
     let path = home.join(".config/starship.toml");
-    write_config(&path, &content)?;
+    journal.write(&path, &content)?;
     Ok("Starship config applied (next prompt picks it up)".to_string())
 }
 
 // ── tmux ────────────────────────────────────────────────────────────
 
-fn apply_tmux(config: &GhosttyConfig) -> Result<String, String> {
-    let home = home_dir()?;
+pub(crate) fn apply_tmux(config: &GhosttyConfig, target: &ApplyTarget, journal: &mut Journal) -> Result<String, String> {
+    let home = target.home_dir()?;
     let content = render_apply_template(config, "apply/tmux_config.tera")?;
     let path = home.join(".tmux.conf");
-    write_config(&path, &content)?;
-
-    // Post-hook: source the config if tmux is running
-    let _ = Command::new("tmux")
-        .args(["source-file", &path.to_string_lossy()])
-        .output();
-
-    Ok("tmux config applied and sourced".to_string())
+    journal.write(&path, &content)?;
+    Ok("tmux config applied".to_string())
 }
 
 // ── Neovim ──────────────────────────────────────────────────────────
 
-fn apply_neovim(config: &GhosttyConfig) -> Result<String, String> {
-    let home = home_dir()?;
+pub(crate) fn apply_neovim(config: &GhosttyConfig, target: &ApplyTarget, journal: &mut Journal) -> Result<String, String> {
+    let home = target.home_dir()?;
     let content = render_apply_template(config, "apply/neovim_config.tera")?;
     let path = home.join(".config/nvim/init.lua");
-    write_config(&path, &content)?;
-    Ok("Neovim config applied (manual :source to reload)".to_string())
+    journal.write(&path, &content)?;
+    Ok("Neovim config applied".to_string())
 }
 
 // ── Tool Configs (Atuin + Lazygit) ──────────────────────────────────
 
-fn apply_tool_configs(config: &GhosttyConfig) -> Result<String, String> {
-    let home = home_dir()?;
+pub(crate) fn apply_tool_configs(config: &GhosttyConfig, target: &ApplyTarget, journal: &mut Journal) -> Result<String, String> {
+    let home = target.home_dir()?;
     let mut messages = Vec::new();
 
     // Atuin
-    let atuin_content = render_apply_template(config, "apply/atuin_config.tera")?;
+    let atuin_content = render_checked(config, "apply/atuin_config.tera", Syntax::Toml)?;
     let atuin_path = home.join(".config/atuin/config.toml");
-    write_config(&atuin_path, &atuin_content)?;
+    journal.write(&atuin_path, &atuin_content)?;
     messages.push("Atuin");
 
     // Lazygit (macOS path)
-    let lazygit_content = render_apply_template(config, "apply/lazygit_config.tera")?;
+    let lazygit_content = render_checked(config, "apply/lazygit_config.tera", Syntax::Yaml)?;
     let lazygit_path = home.join("Library/Application Support/lazygit/config.yml");
-    write_config(&lazygit_path, &lazygit_content)?;
+    journal.write(&lazygit_path, &lazygit_content)?;
     messages.push("Lazygit");
 
     Ok(format!("Tool configs applied: {}", messages.join(", ")))
@@ -119,94 +213,191 @@ fn apply_tool_configs(config: &GhosttyConfig) -> Result<String, String> {
 
 // ── Git ─────────────────────────────────────────────────────────────
 
-fn apply_git(config: &GhosttyConfig) -> Result<String, String> {
-    let delta = &config.git.delta;
-
-    // Set delta config via git config --global
-    let settings = vec![
-        ("core.pager", "delta".to_string()),
-        ("interactive.diffFilter", "delta --color-only".to_string()),
-        ("delta.navigate", delta.navigate.to_string()),
-        ("delta.dark", delta.dark.to_string()),
-        ("delta.line-numbers", delta.line_numbers.to_string()),
-        ("delta.side-by-side", delta.side_by_side.to_string()),
-        ("delta.syntax-theme", delta.syntax_theme.clone()),
-        ("delta.file-style", delta.file_style.clone()),
-        ("delta.file-decoration-style", delta.file_decoration_style.clone()),
-        ("delta.hunk-header-style", delta.hunk_header_style.clone()),
-        ("delta.hunk-header-decoration-style", delta.hunk_header_decoration_style.clone()),
-        ("delta.minus-style", delta.minus_style.clone()),
-        ("delta.plus-style", delta.plus_style.clone()),
-        ("delta.minus-emph-style", delta.minus_emph_style.clone()),
-        ("delta.plus-emph-style", delta.plus_emph_style.clone()),
-        ("delta.zero-style", delta.zero_style.clone()),
-        ("delta.whitespace-error-style", delta.whitespace_error_style.clone()),
-        ("merge.conflictstyle", config.git.merge_conflictstyle.clone()),
-        ("diff.algorithm", config.git.diff_algorithm.clone()),
-        ("diff.colorMoved", config.git.diff_color_moved.clone()),
-        ("pull.rebase", config.git.pull_rebase.to_string()),
-        ("rebase.autostash", config.git.rebase_autostash.to_string()),
-        ("push.autoSetupRemote", config.git.push_auto_setup_remote.to_string()),
-        ("push.default", config.git.push_default.clone()),
-        ("init.defaultBranch", config.git.init_default_branch.clone()),
-        ("core.editor", config.git.core_editor.clone()),
-        ("help.autocorrect", config.git.help_autocorrect.to_string()),
-    ];
-
-    for (key, value) in &settings {
-        let _ = Command::new("git")
-            .args(["config", "--global", key, value])
-            .output();
-    }
-
-    // Set aliases
-    for alias in &config.git.aliases {
-        if alias.enabled {
-            let key = format!("alias.{}", alias.name);
-            let _ = Command::new("git")
-                .args(["config", "--global", &key, &alias.command])
-                .output();
+/// Managed gitconfig, relative to the target home.
+pub(crate) const MANAGED_GITCONFIG: &str = ".config/git/ghostty-ultimate.gitconfig";
+
+pub(crate) fn apply_git(config: &GhosttyConfig, target: &ApplyTarget, journal: &mut Journal) -> Result<String, String> {
+    let home = target.home_dir()?;
+    let managed = home.join(MANAGED_GITCONFIG);
+    // git expands `~` in include paths, so the entry stays valid for any home
+    let include = format!("~/{}", MANAGED_GITCONFIG);
+    journal.track(&target.git_config_path()?);
+
+    if config.git.apply_mode == GitApplyMode::Remove {
+        unset_git_include(target, &include)?;
+        journal.remove(&managed)?;
+        remove_identity_files(&home, journal)?;
+        journal.remove(&home.join(GitSigningConfig::ALLOWED_SIGNERS_FILE))?;
+        return Ok("Managed git include removed".to_string());
+    }
+
+    let signing = &config.git.signing;
+    if signing.enabled {
+        signing::validate_key(signing, &home)?;
+    }
+    let identities = write_identity_files(config, &home, journal)?;
+
+    let only_if_unset = config.git.apply_mode == GitApplyMode::OnlyIfUnset;
+    let mut kept = Vec::new();
+    journal.write(&managed, "")?;
+    for (key, value) in config.git.managed_settings(&config.palette) {
+        if only_if_unset && user_git_value(target, &key)?.is_some() {
+            kept.push(key);
+            continue;
+        }
+        run_git_config(
+            Command::new("git").arg("config").arg("--file").arg(&managed).args([key.as_str(), value.as_str()]),
+            &key,
+        )?;
+    }
+
+    if signing.enabled && signing.format == SigningFormat::Ssh && signing.allowed_signers {
+        let email = user_git_value(target, "user.email")?;
+        let signers = signing::allowed_signers(config, &home, email.as_deref())?;
+        journal.write(&home.join(GitSigningConfig::ALLOWED_SIGNERS_FILE), &signers)?;
+    }
+
+    let includes = user_git_values(target, "include.path")?;
+    if config.git.apply_mode == GitApplyMode::Override && includes.last() != Some(&include) {
+        // Re-add so the include comes after every user setting
+        unset_git_include(target, &include)?;
+        add_git_include(target, &include)?;
+    } else if !includes.contains(&include) {
+        add_git_include(target, &include)?;
+    }
+
+    let mut message = format!("Git settings written to {}", managed.display());
+    if identities > 0 {
+        message.push_str(&format!(" with {} identities", identities));
+    }
+    if signing.enabled {
+        message.push_str(&format!(", signing with {}", signing.key));
+    }
+    if !kept.is_empty() {
+        message.push_str(&format!(" ({} existing settings kept: {})", kept.len(), kept.join(", ")));
+    }
+    Ok(message)
+}
+
+/// Rewrite one include file per enabled identity next to the managed gitconfig.
+/// Returns how many were written.
+fn write_identity_files(config: &GhosttyConfig, home: &Path, journal: &mut Journal) -> Result<usize, String> {
+    remove_identity_files(home, journal)?;
+    let mut written = 0;
+    for identity in config.git.identities.iter().filter(|i| i.enabled) {
+        if !identity.has_valid_id() {
+            return Err(format!("Invalid git identity id '{}'", identity.id));
+        }
+        let path = home.join(identity.include_file());
+        journal.write(&path, "")?;
+        for (key, value) in identity.settings() {
+            run_git_config(
+                Command::new("git").arg("config").arg("--file").arg(&path).args([key.as_str(), value.as_str()]),
+                &key,
+            )?;
+        }
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// Delete every identity include file, including ones for identities that no longer exist.
+fn remove_identity_files(home: &Path, journal: &mut Journal) -> Result<(), String> {
+    let Ok(entries) = fs::read_dir(home.join(".config/git")) else { return Ok(()) };
+    for path in entries.flatten().map(|e| e.path()) {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        if name.starts_with("ghostty-ultimate-") && name.ends_with(".gitconfig") {
+            journal.remove(&path)?;
         }
     }
+    Ok(())
+}
+
+/// The user's own value for `key`, ignoring anything pulled in by includes.
+fn user_git_value(target: &ApplyTarget, key: &str) -> Result<Option<String>, String> {
+    Ok(user_git_values(target, key)?.pop())
+}
+
+fn user_git_values(target: &ApplyTarget, key: &str) -> Result<Vec<String>, String> {
+    let output = target
+        .git_config()?
+        .args(["--no-includes", "--get-all", key])
+        .output()
+        .map_err(|e| format!("Failed to run git config: {}", e))?;
+    // Exit code 1 means the key is not set
+    Ok(String::from_utf8_lossy(&output.stdout).lines().map(|l| l.to_string()).collect())
+}
+
+fn add_git_include(target: &ApplyTarget, include: &str) -> Result<(), String> {
+    run_git_config(target.git_config()?.args(["--add", "include.path", include]), "include.path")
+}
+
+pub(crate) fn unset_git_include(target: &ApplyTarget, include: &str) -> Result<(), String> {
+    let output = target
+        .git_config()?
+        .args(["--fixed-value", "--unset-all", "include.path", include])
+        .output()
+        .map_err(|e| format!("Failed to run git config: {}", e))?;
+    // Exit code 5 means there was nothing to unset
+    match output.status.code() {
+        Some(0) | Some(5) => Ok(()),
+        _ => Err(format!(
+            "git config --unset include.path failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
 
-    Ok("Git config applied (immediate effect)".to_string())
+fn run_git_config(cmd: &mut Command, key: &str) -> Result<(), String> {
+    let output = cmd.output().map_err(|e| format!("Failed to run git config: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git config {} failed: {}",
+            key,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
 }
 
 // ── Themes (bat, btop, yazi) ────────────────────────────────────────
 
-fn apply_themes(config: &GhosttyConfig) -> Result<String, String> {
-    let home = home_dir()?;
+pub(crate) fn apply_themes(config: &GhosttyConfig, target: &ApplyTarget, journal: &mut Journal) -> Result<String, String> {
+    let home = target.home_dir()?;
     let mut messages = Vec::new();
 
     // bat theme
     if config.themes.bat_theme_enabled {
         let bat_content = render_apply_template(config, "apply/bat_theme.tera")?;
-        // Get bat config dir
-        let bat_dir = Command::new("bat")
-            .arg("--config-dir")
-            .output()
-            .ok()
-            .and_then(|o| String::from_utf8(o.stdout).ok())
-            .map(|s| PathBuf::from(s.trim()))
-            .unwrap_or_else(|| home.join(".config/bat"));
+        // Get bat config dir (only meaningful for the real home)
+        let bat_dir = if target.is_live() {
+            Command::new("bat")
+                .arg("--config-dir")
+                .output()
+                .ok()
+                .and_then(|o| String::from_utf8(o.stdout).ok())
+                .map(|s| PathBuf::from(s.trim()))
+                .filter(|p| !p.as_os_str().is_empty())
+                .unwrap_or_else(|| home.join(".config/bat"))
+        } else {
+            home.join(".config/bat")
+        };
         let bat_path = bat_dir.join("themes/Ayu Dark.tmTheme");
-        write_config(&bat_path, &bat_content)?;
-        // Post-hook: rebuild bat cache
-        let _ = Command::new("bat").args(["cache", "--build"]).output();
+        journal.write(&bat_path, &bat_content)?;
         messages.push("bat");
     }
 
     // btop
     let btop_content = render_apply_template(config, "apply/btop_config.tera")?;
     let btop_path = home.join(".config/btop/btop.conf");
-    write_config(&btop_path, &btop_content)?;
+    journal.write(&btop_path, &btop_content)?;
     messages.push("btop");
 
     // yazi theme
     if config.themes.yazi_theme_enabled {
-        let yazi_content = render_apply_template(config, "apply/yazi_theme.tera")?;
+        let yazi_content = render_checked(config, "apply/yazi_theme.tera", Syntax::Toml)?;
         let yazi_path = home.join(".config/yazi/theme.toml");
-        write_config(&yazi_path, &yazi_content)?;
+        journal.write(&yazi_path, &yazi_content)?;
         messages.push("yazi theme");
     }
 
@@ -215,57 +406,58 @@ fn apply_themes(config: &GhosttyConfig) -> Result<String, String> {
 
 // ── Fastfetch ───────────────────────────────────────────────────────
 
-fn apply_fastfetch(config: &GhosttyConfig) -> Result<String, String> {
-    let home = home_dir()?;
+pub(crate) fn apply_fastfetch(config: &GhosttyConfig, target: &ApplyTarget, journal: &mut Journal) -> Result<String, String> {
+    let home = target.home_dir()?;
     let ff_dir = home.join(".config/fastfetch");
 
     // Write quotes file
     let quotes_content = config.fastfetch.quotes.join("\n");
     let quotes_path = ff_dir.join("quotes.txt");
-    write_config(&quotes_path, &quotes_content)?;
+    journal.write(&quotes_path, &quotes_content)?;
 
     // Write config.jsonc
-    let content = render_apply_template(config, "apply/fastfetch_config.tera")?;
+    let content = render_checked(config, "apply/fastfetch_config.tera", Syntax::Jsonc)?;
     let config_path = ff_dir.join("config.jsonc");
-    write_config(&config_path, &content)?;
+    journal.write(&config_path, &content)?;
 
     Ok("Fastfetch config applied".to_string())
 }
 
 // ── Zsh Plugins (yazi config only) ──────────────────────────────────
 
-fn apply_zsh_plugins(config: &GhosttyConfig) -> Result<String, String> {
-    let home = home_dir()?;
+pub(crate) fn apply_zsh_plugins(config: &GhosttyConfig, target: &ApplyTarget, journal: &mut Journal) -> Result<String, String> {
+    let home = target.home_dir()?;
 
     // Write yazi config
-    let content = render_apply_template(config, "apply/yazi_config.tera")?;
+    let content = render_checked(config, "apply/yazi_config.tera", Syntax::Toml)?;
     let path = home.join(".config/yazi/yazi.toml");
-    write_config(&path, &content)?;
+    journal.write(&path, &content)?;
 
     Ok("Yazi config applied".to_string())
 }
 
 // ── Zshrc ───────────────────────────────────────────────────────────
 
-fn apply_zshrc(config: &GhosttyConfig) -> Result<String, String> {
-    let home = home_dir()?;
+/// Lines around the managed block in .zshrc. Must match apply/zshrc_config.tera.
+pub(crate) const ZSHRC_START_MARKER: &str = "# ══ GHOSTTY ULTIMATE ══════════════════════════════════════════";
+pub(crate) const ZSHRC_END_MARKER: &str = "# ══ END GHOSTTY ULTIMATE ═════════════════════════════════════";
+
+pub(crate) fn apply_zshrc(config: &GhosttyConfig, target: &ApplyTarget, journal: &mut Journal) -> Result<String, String> {
+    let home = target.home_dir()?;
     let zshrc_path = home.join(".zshrc");
 
     let new_block = render_apply_template(config, "apply/zshrc_config.tera")?;
 
-    let start_marker = "# ══ GHOSTTY ULTIMATE ══════════════════════════════════════════";
-    let end_marker = "# ══ END GHOSTTY ULTIMATE ═════════════════════════════════════";
-
     // Read existing .zshrc if it exists
     let existing = fs::read_to_string(&zshrc_path).unwrap_or_default();
 
     let final_content = if let (Some(start_idx), Some(end_idx)) = (
-        existing.find(start_marker),
-        existing.find(end_marker),
+        existing.find(ZSHRC_START_MARKER),
+        existing.find(ZSHRC_END_MARKER),
     ) {
         // Replace existing Ghostty Ultimate block
         let before = &existing[..start_idx];
-        let after_end = end_idx + end_marker.len();
+        let after_end = end_idx + ZSHRC_END_MARKER.len();
         let after = if after_end < existing.len() {
             &existing[after_end..]
         } else {
@@ -281,6 +473,156 @@ fn apply_zshrc(config: &GhosttyConfig) -> Result<String, String> {
         }
     };
 
-    write_config(&zshrc_path, &final_content)?;
+    journal.write(&zshrc_path, &final_content)?;
     Ok("Zshrc applied (run 'source ~/.zshrc' to reload)".to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(test: &str) -> (PathBuf, ApplyTarget) {
+        let root = std::env::temp_dir().join(format!("ghostty-ultimate-applier-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        (root.clone(), ApplyTarget { root: Some(root.to_string_lossy().to_string()) })
+    }
+
+    #[test]
+    fn alternate_roots_must_be_absolute_and_are_created() {
+        let relative = ApplyTarget { root: Some("staging/home".into()) };
+        assert!(relative.home_dir().unwrap_err().contains("absolute"));
+
+        let (root, target) = target("root");
+        assert!(!target.is_live());
+        assert_eq!(target.home_dir().unwrap(), root);
+        assert!(root.is_dir());
+        // The XDG gitconfig is only preferred for the real home
+        fs::create_dir_all(root.join(".config/git")).unwrap();
+        fs::write(root.join(".config/git/config"), "").unwrap();
+        assert_eq!(target.git_config_path().unwrap(), root.join(".gitconfig"));
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn sections_apply_under_the_root_without_reloading() {
+        let (root, target) = target("apply");
+        let config = GhosttyConfig::default();
+        for section in ["starship", "tmux", "neovim"] {
+            apply_section(&config, section, &target).unwrap();
+            assert!(reload_hooks(section, &target).unwrap().is_empty());
+        }
+        for path in [".config/starship.toml", ".tmux.conf", ".config/nvim/init.lua"] {
+            assert!(root.join(path).is_file(), "{}", path);
+        }
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    fn git_values(file: &Path, key: &str) -> Vec<String> {
+        let output = Command::new("git").arg("config").arg("--file").arg(file).args(["--get-all", key]).output().unwrap();
+        String::from_utf8_lossy(&output.stdout).lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn git_settings_go_to_the_managed_include() {
+        let (root, target) = target("git");
+        fs::create_dir_all(&root).unwrap();
+        let gitconfig = root.join(".gitconfig");
+        fs::write(&gitconfig, "[user]\n\tname = Me\n[core]\n\tpager = less\n").unwrap();
+        let managed = root.join(MANAGED_GITCONFIG);
+        let include = format!("~/{}", MANAGED_GITCONFIG);
+        let mut config = GhosttyConfig::default();
+
+        // Only-if-unset leaves the user's pager out of the managed file
+        let message = apply_section(&config, "git", &target).unwrap();
+        assert!(message.contains("existing settings kept: core.pager"), "{}", message);
+        assert!(git_values(&managed, "core.pager").is_empty());
+        assert_eq!(git_values(&managed, "delta.navigate"), ["true"]);
+
+        config.git.apply_mode = GitApplyMode::Override;
+        apply_section(&config, "git", &target).unwrap();
+        apply_section(&config, "git", &target).unwrap();
+        assert_eq!(git_values(&managed, "core.pager"), ["delta"]);
+        assert_eq!(git_values(&gitconfig, "include.path"), [include.as_str()]);
+        assert_eq!(git_values(&gitconfig, "user.name"), ["Me"]);
+        assert_eq!(git_values(&gitconfig, "core.pager"), ["less"]);
+
+        config.git.apply_mode = GitApplyMode::Remove;
+        apply_section(&config, "git", &target).unwrap();
+        assert!(!managed.exists());
+        assert!(git_values(&gitconfig, "include.path").is_empty());
+        assert_eq!(git_values(&gitconfig, "user.name"), ["Me"]);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn identity_files_are_rewritten_from_the_config() {
+        let (root, target) = target("identities");
+        let identity = |id: &str| crate::config::sections::GitIdentity {
+            id: id.into(),
+            enabled: true,
+            user_name: "Me".into(),
+            email: format!("me@{}.example", id),
+            signing_key: String::new(),
+            directories: vec![format!("~/{}", id)],
+        };
+        let mut config = GhosttyConfig::default();
+        config.git.identities = vec![identity("work"), identity("oss")];
+        let message = apply_section(&config, "git", &target).unwrap();
+        assert!(message.contains("with 2 identities"), "{}", message);
+        let work = root.join(".config/git/ghostty-ultimate-work.gitconfig");
+        assert_eq!(git_values(&work, "user.email"), ["me@work.example"]);
+        let managed = root.join(MANAGED_GITCONFIG);
+        assert_eq!(git_values(&managed, "includeIf.gitdir:~/oss/.path"), ["~/.config/git/ghostty-ultimate-oss.gitconfig"]);
+
+        // Identities removed from the config lose their file
+        config.git.identities = vec![identity("work")];
+        apply_section(&config, "git", &target).unwrap();
+        assert!(work.exists());
+        assert!(!root.join(".config/git/ghostty-ultimate-oss.gitconfig").exists());
+
+        // An invalid id fails the apply and leaves the previous files in place
+        config.git.identities = vec![identity("../work")];
+        assert!(apply_section(&config, "git", &target).unwrap_err().contains("Invalid git identity"));
+        assert!(work.exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    /// `key = value` lines of a Ghostty config, ignoring alignment.
+    fn ghostty_settings(text: &str) -> Vec<String> {
+        text.lines()
+            .filter(|l| !l.starts_with('#') && !l.starts_with("config-file"))
+            .filter_map(|l| l.split_once('=').map(|(key, value)| format!("{}={}", key.trim(), value.trim())))
+            .collect()
+    }
+
+    #[test]
+    fn the_modular_layout_splits_the_same_settings_into_includes() {
+        let (root, target) = target("modular");
+        let mut config = GhosttyConfig { palette_name: "Tokyo Night".into(), ..GhosttyConfig::default() };
+        apply_section(&config, "ghostty", &target).unwrap();
+        let dir = root.join(".config/ghostty");
+        let mut single = ghostty_settings(&fs::read_to_string(dir.join("config")).unwrap());
+        assert!(dir.join("themes/tokyo-night").is_file());
+
+        config.ghostty.layout = GhosttyLayout::Modular;
+        apply_section(&config, "ghostty", &target).unwrap();
+        let base = fs::read_to_string(dir.join("config")).unwrap();
+        let includes: Vec<&str> = base.lines().filter_map(|l| l.strip_prefix("config-file = ")).collect();
+        let expected: Vec<&str> = GHOSTTY_INCLUDES.iter().map(|(name, _)| *name).chain(["?local"]).collect();
+        assert_eq!(includes, expected);
+
+        let mut modular = ghostty_settings(&base);
+        for (name, _) in GHOSTTY_INCLUDES {
+            modular.extend(ghostty_settings(&fs::read_to_string(dir.join(name)).unwrap()));
+        }
+        single.sort();
+        modular.sort();
+        assert_eq!(modular, single);
+
+        // `local` is the user's once it exists
+        fs::write(dir.join("local"), "font-size = 20\n").unwrap();
+        apply_section(&config, "ghostty", &target).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("local")).unwrap(), "font-size = 20\n");
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,603 @@
+use crate::config::requirements::Requirement;
+use crate::config::schema::GhosttyConfig;
+use crate::executor::reload::{self, ReloadResult};
+use super::engine::{self, Syntax};
+use super::registry;
+use super::transaction::Journal;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Custom section manifests, relative to the home directory. Each
+/// subdirectory holds a `section.json` and the templates it names.
+pub const CUSTOM_SECTIONS_DIR: &str = ".config/ghostty-ultimate-ui/sections";
+
+pub const MANIFEST_FILE: &str = "section.json";
+
+/// Custom templates are registered as `custom/<id>/<path>`.
+const TEMPLATE_PREFIX: &str = "custom/";
+
+/// Heredoc delimiter the install script writes custom files with.
+pub const HEREDOC_DELIMITER: &str = "GHOSTTY_ULTIMATE_CUSTOM_EOF";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionManifest {
+    /// Section id, used in `custom_sections` and the script's `--only`.
+    pub id: String,
+    pub label: String,
+    #[serde(default)]
+    pub icon: String,
+    #[serde(default)]
+    pub description: String,
+    /// JSON Schema for the settings block; templates see the validated
+    /// values, with defaults filled in, as `section`.
+    #[serde(default = "empty_schema")]
+    pub settings: Value,
+    #[serde(default)]
+    pub files: Vec<ManifestFile>,
+    /// Template with extra shell steps, run by the install script after
+    /// the files are written.
+    #[serde(default)]
+    pub script: Option<String>,
+    /// Run after the section is applied to the real home.
+    #[serde(default)]
+    pub reload: Vec<ReloadHook>,
+    /// Packages, fonts and sections the section needs, e.g. `{"package": "wezterm"}`.
+    #[serde(default)]
+    pub requires: Vec<Requirement>,
+    /// Directory the manifest was loaded from.
+    #[serde(default)]
+    pub dir: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestFile {
+    /// Template path relative to the manifest directory.
+    pub template: String,
+    /// Destination relative to the home directory.
+    pub target: String,
+    /// Parse-check the rendered file before it is written.
+    #[serde(default)]
+    pub syntax: Option<Syntax>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ReloadHook {
+    /// One of the built-in hooks, e.g. `"tmux"`.
+    Builtin(String),
+    /// A command, e.g. `{ "command": ["wezterm", "cli", "reload-config"] }`.
+    Command { command: Vec<String> },
+}
+
+/// Every manifest found, plus the ones that could not be loaded.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CustomSections {
+    pub sections: Vec<SectionManifest>,
+    pub errors: Vec<String>,
+}
+
+impl CustomSections {
+    pub fn get(&self, id: &str) -> Option<&SectionManifest> {
+        self.sections.iter().find(|s| s.id == id)
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.sections.iter().map(|s| s.id.as_str())
+    }
+}
+
+fn empty_schema() -> Value {
+    serde_json::json!({ "type": "object", "properties": {} })
+}
+
+// ── Loading ─────────────────────────────────────────────────────────
+
+fn is_relative_normal(path: &str) -> bool {
+    !path.is_empty() && Path::new(path).components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Targets are written from the install script as `"$HOME/<target>"`.
+fn is_safe_target(target: &str) -> bool {
+    is_relative_normal(target)
+        && target.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '/'))
+}
+
+fn check_manifest(manifest: &SectionManifest, dir: &Path) -> Result<(), String> {
+    let id_ok = manifest.id.starts_with(|c: char| c.is_ascii_lowercase())
+        && manifest.id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !id_ok {
+        return Err("id may only contain lowercase letters, digits and '_'".into());
+    }
+    if registry::builtin(&manifest.id).is_some() {
+        return Err(format!("id '{}' is a built-in section", manifest.id));
+    }
+    if manifest.settings.get("type").and_then(Value::as_str) != Some("object") {
+        return Err("settings must be a JSON Schema of type \"object\"".into());
+    }
+    let templates = manifest.files.iter().map(|f| &f.template).chain(manifest.script.iter());
+    for template in templates {
+        if !is_relative_normal(template) || !dir.join(template).is_file() {
+            return Err(format!("template '{}' not found in {}", template, dir.display()));
+        }
+    }
+    for file in &manifest.files {
+        if !is_safe_target(&file.target) {
+            return Err(format!("target '{}' must be a relative path of letters, digits, '.', '_', '-' and '/'", file.target));
+        }
+    }
+    for hook in &manifest.reload {
+        match hook {
+            ReloadHook::Builtin(name) if !reload::HOOKS.contains(&name.as_str()) => {
+                return Err(format!("unknown reload hook '{}'", name));
+            }
+            ReloadHook::Command { command } if command.is_empty() => {
+                return Err("reload command is empty".into());
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Read every `section.json` under `<home>/.config/ghostty-ultimate-ui/sections`.
+pub fn load(home: &Path) -> CustomSections {
+    let mut found = CustomSections::default();
+    let Ok(entries) = fs::read_dir(home.join(CUSTOM_SECTIONS_DIR)) else { return found };
+    let mut dirs: Vec<PathBuf> = entries.flatten().map(|e| e.path()).filter(|p| p.join(MANIFEST_FILE).is_file()).collect();
+    dirs.sort();
+
+    for dir in dirs {
+        let path = dir.join(MANIFEST_FILE);
+        let manifest = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_json::from_str::<SectionManifest>(&json).map_err(|e| e.to_string()))
+            .and_then(|m| check_manifest(&m, &dir).map(|_| m));
+        match manifest {
+            Ok(m) if found.get(&m.id).is_some() => {
+                found.errors.push(format!("{}: section id is already defined", path.display()));
+            }
+            Ok(mut m) => {
+                m.dir = dir.to_string_lossy().to_string();
+                found.sections.push(m);
+            }
+            Err(e) => found.errors.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+    found
+}
+
+static REGISTRY: OnceLock<RwLock<Arc<CustomSections>>> = OnceLock::new();
+
+fn registry_lock() -> &'static RwLock<Arc<CustomSections>> {
+    REGISTRY.get_or_init(|| {
+        let loaded = dirs::home_dir().map(|home| load(&home)).unwrap_or_default();
+        RwLock::new(Arc::new(loaded))
+    })
+}
+
+/// Custom sections loaded at startup (or by the last `reload`).
+pub fn registry() -> Arc<CustomSections> {
+    Arc::clone(&registry_lock().read().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Rescan the manifest directory.
+pub fn reload() -> Arc<CustomSections> {
+    let loaded = Arc::new(dirs::home_dir().map(|home| load(&home)).unwrap_or_default());
+    *registry_lock().write().unwrap_or_else(|e| e.into_inner()) = Arc::clone(&loaded);
+    loaded
+}
+
+/// Every `.tera` file in the registered sections' directories, by the name
+/// it is registered under, so templates can include their own partials.
+pub fn template_files(sections: &CustomSections) -> Vec<(String, PathBuf)> {
+    fn walk(root: &Path, dir: &Path, prefix: &str, out: &mut Vec<(String, PathBuf)>) {
+        let Ok(entries) = fs::read_dir(dir) else { return };
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.is_dir() {
+                walk(root, &path, prefix, out);
+            } else if path.extension().is_some_and(|e| e == "tera") {
+                let relative = path.strip_prefix(root).unwrap_or(&path);
+                let relative = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+                out.push((format!("{}{}", prefix, relative), path));
+            }
+        }
+    }
+    let mut files = Vec::new();
+    for section in &sections.sections {
+        let dir = PathBuf::from(&section.dir);
+        walk(&dir, &dir, &template_name(&section.id, ""), &mut files);
+    }
+    files.sort();
+    files
+}
+
+fn template_name(id: &str, path: &str) -> String {
+    format!("{}{}/{}", TEMPLATE_PREFIX, id, path)
+}
+
+// ── Settings ────────────────────────────────────────────────────────
+
+fn type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+/// Check `value` against the subset of JSON Schema the settings editor
+/// understands: type, enum, minimum/maximum, minLength/maxLength, pattern,
+/// properties, required, additionalProperties and items.
+pub fn validate_value(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    if !types.is_empty() && !types.iter().any(|t| type_matches(t, value)) {
+        errors.push(format!("{} should be {}", path, types.join(" or ")));
+        return;
+    }
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            let allowed: Vec<String> = options.iter().map(|o| o.to_string()).collect();
+            errors.push(format!("{} should be one of {}", path, allowed.join(", ")));
+        }
+    }
+    if let Some(n) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(Value::as_f64).filter(|min| n < *min) {
+            errors.push(format!("{} should be at least {}", path, min));
+        }
+        if let Some(max) = schema.get("maximum").and_then(Value::as_f64).filter(|max| n > *max) {
+            errors.push(format!("{} should be at most {}", path, max));
+        }
+    }
+    if let Some(s) = value.as_str() {
+        let len = s.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(Value::as_u64).filter(|min| len < *min) {
+            errors.push(format!("{} should be at least {} characters", path, min));
+        }
+        if let Some(max) = schema.get("maxLength").and_then(Value::as_u64).filter(|max| len > *max) {
+            errors.push(format!("{} should be at most {} characters", path, max));
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+            match regex::Regex::new(pattern) {
+                Ok(re) if !re.is_match(s) => errors.push(format!("{} should match {}", path, pattern)),
+                Ok(_) => {}
+                Err(e) => errors.push(format!("{}: invalid pattern in schema: {}", path, e)),
+            }
+        }
+    }
+    if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+        for (i, item) in array.iter().enumerate() {
+            validate_value(items, item, &format!("{}[{}]", path, i), errors);
+        }
+    }
+    if let Some(object) = value.as_object() {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for key in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+            if !object.contains_key(key) {
+                errors.push(format!("{}.{} is required", path, key));
+            }
+        }
+        for (key, item) in object {
+            match properties.and_then(|p| p.get(key)) {
+                Some(property) => validate_value(property, item, &format!("{}.{}", path, key), errors),
+                None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                    errors.push(format!("{}.{} is not a known setting", path, key));
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+/// `value` with every missing property that has a schema default filled in.
+pub fn with_defaults(schema: &Value, value: &Value) -> Value {
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return value.clone();
+    };
+    let mut object = match value {
+        Value::Object(map) => map.clone(),
+        Value::Null => Map::new(),
+        other => return other.clone(),
+    };
+    for (key, property) in properties {
+        match object.get(key) {
+            Some(existing) => {
+                let filled = with_defaults(property, existing);
+                object.insert(key.clone(), filled);
+            }
+            None => {
+                if let Some(default) = property.get("default") {
+                    object.insert(key.clone(), with_defaults(property, default));
+                } else if property.get("type").and_then(Value::as_str) == Some("object") {
+                    object.insert(key.clone(), with_defaults(property, &Value::Null));
+                }
+            }
+        }
+    }
+    Value::Object(object)
+}
+
+/// A section's settings from the config, with schema defaults applied.
+pub fn settings(config: &GhosttyConfig, manifest: &SectionManifest) -> Value {
+    let value = config.custom_sections.get(&manifest.id).map(|c| c.settings.clone()).unwrap_or(Value::Null);
+    with_defaults(&manifest.settings, &value)
+}
+
+pub fn is_enabled(config: &GhosttyConfig, id: &str) -> bool {
+    config.custom_sections.get(id).is_some_and(|c| c.enabled)
+}
+
+/// Warnings for `schema::validate`: load errors, configs without a
+/// manifest and settings that do not match their schema.
+pub fn validate(config: &GhosttyConfig) -> Vec<String> {
+    let sections = registry();
+    let mut warnings: Vec<String> = sections.errors.iter().map(|e| format!("Custom section {}", e)).collect();
+    for (id, section) in &config.custom_sections {
+        match sections.get(id) {
+            None if section.enabled => warnings.push(format!("Custom section '{}' is enabled but has no manifest", id)),
+            None => {}
+            Some(manifest) => {
+                let mut errors = Vec::new();
+                validate_value(&manifest.settings, &settings(config, manifest), id, &mut errors);
+                warnings.extend(errors.into_iter().map(|e| format!("Custom section setting {}", e)));
+            }
+        }
+    }
+    warnings
+}
+
+// ── Rendering ───────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderedFile {
+    pub target: String,
+    pub content: String,
+}
+
+/// A section rendered for the install script.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderedSection {
+    pub id: String,
+    pub label: String,
+    pub icon: String,
+    pub files: Vec<RenderedFile>,
+    pub script: String,
+}
+
+fn render_template(config: &GhosttyConfig, manifest: &SectionManifest, template: &str) -> Result<String, String> {
+    let compiled = engine::compiled()?;
+    let name = template_name(&manifest.id, template);
+    if let Some((_, error)) = compiled.template_errors.iter().find(|(n, _)| *n == name) {
+        return Err(format!("Custom section '{}': {}", manifest.id, error));
+    }
+    let mut ctx = engine::build_context(config)?;
+    ctx.insert("section", &settings(config, manifest));
+    ctx.insert("section_id", &manifest.id);
+    compiled.tera.render(&name, &ctx)
+        .map_err(|e| format!("Custom section '{}': {}", manifest.id, engine::error_chain(&e)))
+}
+
+/// Render every file of a section, parse-checking those with a syntax.
+pub fn render_files(config: &GhosttyConfig, manifest: &SectionManifest) -> Result<Vec<RenderedFile>, String> {
+    let mut errors = Vec::new();
+    validate_value(&manifest.settings, &settings(config, manifest), &manifest.id, &mut errors);
+    if !errors.is_empty() {
+        return Err(format!("Custom section settings are invalid: {}", errors.join("; ")));
+    }
+    manifest
+        .files
+        .iter()
+        .map(|file| {
+            let content = render_template(config, manifest, &file.template)?;
+            if let Some(syntax) = file.syntax {
+                engine::check_syntax(&content, syntax).map_err(|e| format!("{} rendered {}", file.target, e))?;
+            }
+            Ok(RenderedFile { target: file.target.clone(), content })
+        })
+        .collect()
+}
+
+pub fn render_section(config: &GhosttyConfig, manifest: &SectionManifest) -> Result<RenderedSection, String> {
+    let mut files = render_files(config, manifest)?;
+    for file in files.iter_mut() {
+        // The heredoc adds the final newline back
+        if file.content.ends_with('\n') {
+            file.content.pop();
+        }
+    }
+    if let Some(file) = files.iter().find(|f| f.content.lines().any(|l| l == HEREDOC_DELIMITER)) {
+        return Err(format!("{} contains the line {}", file.target, HEREDOC_DELIMITER));
+    }
+    let script = match &manifest.script {
+        Some(template) => render_template(config, manifest, template)?,
+        None => String::new(),
+    };
+    Ok(RenderedSection { id: manifest.id.clone(), label: manifest.label.clone(), icon: manifest.icon.clone(), files, script })
+}
+
+// ── Applying ────────────────────────────────────────────────────────
+
+pub fn apply(config: &GhosttyConfig, manifest: &SectionManifest, home: &Path, journal: &mut Journal) -> Result<String, String> {
+    let files = render_files(config, manifest)?;
+    for file in &files {
+        journal.write(&home.join(&file.target), &file.content)?;
+    }
+    Ok(format!("{} config applied ({} file{})", manifest.label, files.len(), if files.len() == 1 { "" } else { "s" }))
+}
+
+/// Run a section's reload hooks against the real home.
+pub fn run_reload_hooks(manifest: &SectionManifest, home: &Path) -> Vec<ReloadResult> {
+    manifest
+        .reload
+        .iter()
+        .filter_map(|hook| match hook {
+            ReloadHook::Builtin(name) => reload::run_hook(name, home).ok(),
+            ReloadHook::Command { command } => Some(reload::run_command(&manifest.id, command)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_must_stay_inside_home_and_be_shell_safe() {
+        assert!(is_safe_target(".config/wezterm/wezterm.lua"));
+        assert!(is_safe_target("notes_v2-final.txt"));
+        for target in ["", "/etc/passwd", "../outside", ".config/../../x", "./here", "a b", "a$HOME", "a\"b", "a`id`", "a\nb"] {
+            assert!(!is_safe_target(target), "{:?}", target);
+        }
+    }
+
+    #[test]
+    fn manifests_with_unsafe_targets_are_not_loaded() {
+        let home = std::env::temp_dir().join(format!("ghostty-ultimate-custom-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        let sections = home.join(CUSTOM_SECTIONS_DIR);
+        for (id, target) in [("good", ".config/good/config"), ("escape", "../escape"), ("quoted", "it's")] {
+            let dir = sections.join(id);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("config.tera"), "x").unwrap();
+            let manifest = serde_json::json!({
+                "id": id,
+                "label": id,
+                "files": [{ "template": "config.tera", "target": target }],
+            });
+            fs::write(dir.join(MANIFEST_FILE), manifest.to_string()).unwrap();
+        }
+
+        let found = load(&home);
+        assert_eq!(found.ids().collect::<Vec<_>>(), vec!["good"]);
+        assert_eq!(found.errors.len(), 2, "{:?}", found.errors);
+        assert!(found.errors.iter().all(|e| e.contains("must be a relative path")), "{:?}", found.errors);
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    fn manifest(settings: Value) -> SectionManifest {
+        serde_json::from_value(serde_json::json!({ "id": "wezterm", "label": "WezTerm", "settings": settings })).unwrap()
+    }
+
+    #[test]
+    fn settings_are_checked_against_their_schema() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "required": ["font"],
+            "additionalProperties": false,
+            "properties": {
+                "font": { "type": "string", "minLength": 1, "pattern": "^[A-Za-z ]+$" },
+                "size": { "type": "integer", "minimum": 6, "maximum": 72 },
+                "cursor": { "enum": ["block", "bar"] },
+                "tabs": { "type": "array", "items": { "type": ["string", "null"] } },
+            },
+        });
+        let check = |value: Value| {
+            let mut errors = Vec::new();
+            validate_value(&schema, &value, "wezterm", &mut errors);
+            errors
+        };
+        assert!(check(serde_json::json!({ "font": "Fira Code", "size": 13, "tabs": ["a", null] })).is_empty());
+        assert_eq!(
+            check(serde_json::json!({ "size": 12.5, "cursor": "beam", "tabs": [1], "theme": "x" })),
+            [
+                "wezterm.font is required",
+                "wezterm.cursor should be one of \"block\", \"bar\"",
+                "wezterm.size should be integer",
+                "wezterm.tabs[0] should be string or null",
+                "wezterm.theme is not a known setting",
+            ]
+        );
+        assert_eq!(
+            check(serde_json::json!({ "font": "Fira_Code", "size": 100 })),
+            ["wezterm.font should match ^[A-Za-z ]+$", "wezterm.size should be at most 72"]
+        );
+        assert_eq!(check(serde_json::json!("Fira Code")), ["wezterm should be object"]);
+    }
+
+    #[test]
+    fn missing_settings_take_the_schema_defaults() {
+        let section = manifest(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "font": { "type": "string", "default": "JetBrains Mono" },
+                "window": {
+                    "type": "object",
+                    "properties": { "opacity": { "type": "number", "default": 0.9 }, "title": { "type": "string" } },
+                },
+            },
+        }));
+        let mut config = GhosttyConfig::default();
+        assert!(!is_enabled(&config, "wezterm"));
+        assert_eq!(
+            settings(&config, &section),
+            serde_json::json!({ "font": "JetBrains Mono", "window": { "opacity": 0.9 } })
+        );
+
+        config.custom_sections.insert(
+            "wezterm".into(),
+            serde_json::from_value(serde_json::json!({ "enabled": true, "settings": { "window": { "opacity": 1 } } })).unwrap(),
+        );
+        assert!(is_enabled(&config, "wezterm"));
+        assert_eq!(
+            settings(&config, &section),
+            serde_json::json!({ "font": "JetBrains Mono", "window": { "opacity": 1 } })
+        );
+
+        config.custom_sections.get_mut("wezterm").unwrap().settings = serde_json::json!({ "font": 12 });
+        let err = render_files(&config, &section).unwrap_err();
+        assert_eq!(err, "Custom section settings are invalid: wezterm.font should be string");
+    }
+
+    #[test]
+    fn invalid_manifests_are_reported_and_templates_are_namespaced() {
+        let home = std::env::temp_dir().join(format!("ghostty-ultimate-custom-load-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        let sections = home.join(CUSTOM_SECTIONS_DIR);
+        let write = |dir: &str, manifest: Value| {
+            let dir = sections.join(dir);
+            fs::create_dir_all(dir.join("lua")).unwrap();
+            fs::write(dir.join("config.tera"), "x").unwrap();
+            fs::write(dir.join("lua/keys.tera"), "x").unwrap();
+            fs::write(dir.join(MANIFEST_FILE), manifest.to_string()).unwrap();
+        };
+        let files = serde_json::json!([{ "template": "config.tera", "target": ".wezterm.lua" }]);
+        write("a", serde_json::json!({ "id": "wezterm", "label": "WezTerm", "files": files, "reload": ["tmux"] }));
+        write("b", serde_json::json!({ "id": "wezterm", "label": "Again" }));
+        write("c", serde_json::json!({ "id": "Bad-Id", "label": "x" }));
+        write("d", serde_json::json!({ "id": "tmux", "label": "x" }));
+        write("e", serde_json::json!({ "id": "missing", "label": "x", "script": "install.tera" }));
+        write("f", serde_json::json!({ "id": "hooks", "label": "x", "reload": ["kitty"] }));
+        write("g", serde_json::json!({ "id": "schema", "label": "x", "settings": { "type": "array" } }));
+        fs::create_dir_all(sections.join("no-manifest")).unwrap();
+
+        let found = load(&home);
+        assert_eq!(found.ids().collect::<Vec<_>>(), ["wezterm"]);
+        let errors: Vec<&str> = found.errors.iter().map(|e| e.split(": ").nth(1).unwrap_or(e)).collect();
+        assert_eq!(
+            errors,
+            [
+                "section id is already defined",
+                "id may only contain lowercase letters, digits and '_'",
+                "id 'tmux' is a built-in section",
+                &format!("template 'install.tera' not found in {}", sections.join("e").display()),
+                "unknown reload hook 'kitty'",
+                "settings must be a JSON Schema of type \"object\"",
+            ]
+        );
+
+        let names: Vec<String> = template_files(&found).into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, ["custom/wezterm/config.tera", "custom/wezterm/lua/keys.tera"]);
+        fs::remove_dir_all(&home).unwrap();
+    }
+}
@@ -1,8 +1,33 @@
 use crate::config::schema::GhosttyConfig;
+use serde::{Deserialize, Serialize};
 use tera::{Tera, Context};
+use super::custom;
 use super::filters;
+use super::overrides;
+use super::registry::{self, SectionInfo};
+use super::selection::SectionSelection;
+use super::uninstall::{self, UninstallOptions};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
 
-fn create_tera() -> Result<Tera, String> {
+/// Template sources in the checkout, watched by debug builds.
+const TEMPLATE_SRC_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/generator/templates");
+
+/// Set to a template directory to hot-reload from it in any build.
+pub const TEMPLATE_DIR_ENV: &str = "GHOSTTY_ULTIMATE_TEMPLATE_DIR";
+
+/// Compile every template. Built-ins are also registered under
+/// `builtin/<name>`, so an override can extend the one it replaces. An
+/// override that fails to compile falls back to the built-in, and a custom
+/// section template that fails is left out; their errors are returned
+/// alongside.
+fn create_tera(
+    builtins: &[(&str, String)],
+    overrides: &[(String, String)],
+    custom: &[(String, String)],
+) -> Result<(Tera, Vec<(String, String)>), String> {
     let mut tera = Tera::default();
 
     // Register custom filters
@@ -10,58 +35,385 @@ fn create_tera() -> Result<Tera, String> {
     tera.register_filter("bool_to_str", filters::bool_to_str);
     tera.register_filter("shell_escape", filters::shell_escape);
     tera.register_filter("hex_strip", filters::hex_strip);
+    tera.register_filter("single_line", filters::single_line);
+    tera.register_filter("toml_escape", filters::toml_escape);
+    tera.register_filter("json_escape", filters::json_escape);
+    tera.register_filter("yaml_escape", filters::yaml_escape);
+    tera.register_filter("lua_escape", filters::lua_escape);
+    tera.register_filter("tmux_escape", filters::tmux_escape);
+    tera.register_filter("nix", filters::nix);
+    tera.register_filter("nix_text", filters::nix_text);
 
-    // Add all templates as raw strings
-    let templates = get_all_templates();
-    for (name, content) in &templates {
+    // Add all built-in templates as raw strings
+    for (name, content) in builtins {
+        tera.add_raw_template(&format!("{}{}", overrides::BUILTIN_PREFIX, name), content)
+            .map_err(|e| format!("Template '{}' error: {}", name, error_chain(&e)))?;
         tera.add_raw_template(name, content)
-            .map_err(|e| format!("Template '{}' error: {}", name, e))?;
+            .map_err(|e| format!("Template '{}' error: {}", name, error_chain(&e)))?;
     }
 
-    // Add apply (config-only) templates
-    let apply_templates = get_apply_templates();
-    for (name, content) in &apply_templates {
-        tera.add_raw_template(name, content)
-            .map_err(|e| format!("Apply template '{}' error: {}", name, e))?;
+    // Layer user overrides on top
+    let mut errors = Vec::new();
+    for (name, content) in overrides {
+        if let Err(e) = tera.add_raw_template(name, content) {
+            errors.push((name.clone(), error_chain(&e)));
+            match builtins.iter().find(|(n, _)| *n == name.as_str()) {
+                Some((_, builtin)) => tera.add_raw_template(name, builtin),
+                None => {
+                    tera.templates.remove(name);
+                    tera.build_inheritance_chains()
+                }
+            }
+            .map_err(|e| format!("Template '{}' error: {}", name, error_chain(&e)))?;
+        }
+    }
+
+    // Custom section templates live in their own namespace
+    for (name, content) in custom {
+        if let Err(e) = tera.add_raw_template(name, content) {
+            errors.push((name.clone(), error_chain(&e)));
+            tera.templates.remove(name);
+            tera.build_inheritance_chains()
+                .map_err(|e| format!("Template '{}' error: {}", name, error_chain(&e)))?;
+        }
+    }
+
+    let extending: Vec<String> = overrides.iter().chain(custom).map(|(name, _)| name.clone()).collect();
+    overrides::flatten_inheritance(&mut tera, &extending)
+        .map_err(|e| format!("Template override error: {}", error_chain(&e)))?;
+
+    Ok((tera, errors))
+}
+
+/// Tera keeps the parse location in the error's source chain.
+pub(crate) fn error_chain(e: &tera::Error) -> String {
+    let mut message = e.to_string();
+    let mut source = std::error::Error::source(e);
+    while let Some(cause) = source {
+        message = format!("{}: {}", message, cause);
+        source = cause.source();
+    }
+    message
+}
+
+fn template_source(dir: Option<&Path>, name: &str, embedded: &str) -> String {
+    dir.and_then(|d| fs::read_to_string(d.join(name)).ok())
+        .unwrap_or_else(|| embedded.to_string())
+}
+
+/// Built-in template sources by name: the embedded copies, or the files on
+/// disk in development mode.
+pub fn builtin_sources() -> Vec<(&'static str, String)> {
+    let dir = dev_template_dir();
+    get_all_templates()
+        .into_iter()
+        .chain(get_apply_templates())
+        .map(|(name, content)| (name, template_source(dir.as_deref(), name, content)))
+        .collect()
+}
+
+// ── Template cache ──────────────────────────────────────────────────
+
+pub(crate) struct CompiledTemplates {
+    pub tera: Arc<Tera>,
+    /// Overrides that failed to compile and were replaced by the built-in,
+    /// and custom section templates that failed to compile.
+    pub template_errors: Vec<(String, String)>,
+    /// Modification times of every source file read from disk.
+    stamp: Vec<(PathBuf, SystemTime)>,
+}
+
+static TERA_CACHE: OnceLock<Mutex<Option<Arc<CompiledTemplates>>>> = OnceLock::new();
+
+/// Where to hot-reload templates from: the env override, else the source
+/// tree in debug builds. Release builds use the embedded set.
+fn dev_template_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var(TEMPLATE_DIR_ENV) {
+        return Some(PathBuf::from(dir));
+    }
+    let src = Path::new(TEMPLATE_SRC_DIR);
+    (cfg!(debug_assertions) && src.is_dir()).then(|| src.to_path_buf())
+}
+
+fn source_stamp(paths: impl Iterator<Item = PathBuf>) -> Vec<(PathBuf, SystemTime)> {
+    paths
+        .filter_map(|p| fs::metadata(&p).and_then(|m| m.modified()).ok().map(|t| (p, t)))
+        .collect()
+}
+
+/// The shared compiled template set. Built once, and rebuilt when a user
+/// override, a custom section template (or, in development mode, a built-in
+/// file) changes on disk.
+pub(crate) fn compiled() -> Result<Arc<CompiledTemplates>, String> {
+    let cache = TERA_CACHE.get_or_init(|| Mutex::new(None));
+    let custom_files = custom::template_files(&custom::registry());
+    compile_cached(cache, dev_template_dir().as_deref(), &overrides::override_files(), &custom_files)
+}
+
+fn compile_cached(
+    cache: &Mutex<Option<Arc<CompiledTemplates>>>,
+    dev_dir: Option<&Path>,
+    override_files: &[(String, PathBuf)],
+    custom_files: &[(String, PathBuf)],
+) -> Result<Arc<CompiledTemplates>, String> {
+    let dev_paths = dev_dir.into_iter().flat_map(|dir| {
+        get_all_templates().into_iter().chain(get_apply_templates()).map(move |(name, _)| dir.join(name))
+    });
+    let user_paths = override_files.iter().chain(custom_files).map(|(_, path)| path.clone());
+    let stamp = source_stamp(dev_paths.chain(user_paths));
+
+    let mut cached = cache.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(c) = cached.as_ref().filter(|c| c.stamp == stamp) {
+        return Ok(Arc::clone(c));
     }
+    let read = |files: &[(String, PathBuf)]| -> Vec<(String, String)> {
+        files
+            .iter()
+            .filter_map(|(name, path)| fs::read_to_string(path).ok().map(|content| (name.clone(), content)))
+            .collect()
+    };
+    let (tera, template_errors) = create_tera(&builtin_sources(), &read(override_files), &read(custom_files))?;
+    let compiled = Arc::new(CompiledTemplates { tera: Arc::new(tera), template_errors, stamp });
+    *cached = Some(Arc::clone(&compiled));
+    Ok(compiled)
+}
 
-    Ok(tera)
+fn tera() -> Result<Arc<Tera>, String> {
+    compiled().map(|c| Arc::clone(&c.tera))
 }
 
-fn build_context(config: &GhosttyConfig) -> Result<Context, String> {
+pub(crate) fn build_context(config: &GhosttyConfig) -> Result<Context, String> {
     let mut ctx = Context::new();
     let json = serde_json::to_value(config).map_err(|e| e.to_string())?;
     ctx.insert("config", &json);
     // Also insert palette at top level for convenience
     let palette_json = serde_json::to_value(&config.palette).map_err(|e| e.to_string())?;
     ctx.insert("palette", &palette_json);
+    ctx.insert("theme_name", &config.theme_name());
+    // Managed gitconfig entries, shared by the script and the applier. The
+    // script writes signing only once the key is found, so the three groups
+    // are kept apart; the includeIf entries go last.
+    let entries = |settings: Vec<(String, String)>| -> Vec<serde_json::Value> {
+        settings.into_iter().map(|(key, value)| serde_json::json!({ "key": key, "value": value })).collect()
+    };
+    ctx.insert("git_settings", &entries(config.git.settings(&config.palette)));
+    ctx.insert("git_signing_settings", &entries(config.git.signing.settings()));
+    ctx.insert("git_include_settings", &entries(config.git.identity_includes()));
+    let git_identities: Vec<serde_json::Value> = config
+        .git
+        .identities
+        .iter()
+        .filter(|i| i.enabled && i.has_valid_id())
+        .map(|i| {
+            let settings: Vec<serde_json::Value> = i
+                .settings()
+                .into_iter()
+                .map(|(key, value)| serde_json::json!({ "key": key, "value": value }))
+                .collect();
+            serde_json::json!({ "id": i.id, "file": i.include_file(), "settings": settings })
+        })
+        .collect();
+    ctx.insert("git_identities", &git_identities);
     Ok(ctx)
 }
 
-pub fn generate(config: &GhosttyConfig) -> Result<String, String> {
-    let tera = create_tera()?;
-    let ctx = build_context(config)?;
-    tera.render("main.sh.tera", &ctx).map_err(|e| format!("Render error: {}", e))
+pub fn generate(config: &GhosttyConfig, selection: &SectionSelection) -> Result<String, String> {
+    selection.validate()?;
+    let tera = tera()?;
+    let mut ctx = build_context(config)?;
+    // Sections are numbered among the ones the script runs
+    let enabled = registry::enabled_sections(config);
+    let emitted: Vec<&str> = enabled.iter().map(|s| s.id.as_str()).collect();
+    if let Some(from) = selection.from.as_deref().filter(|from| !emitted.contains(from)) {
+        return Err(format!("Section '{}' is not enabled, so the script cannot start from it", from));
+    }
+    ctx.insert("section_ids", &emitted);
+    ctx.insert("default_args", &selection.to_args());
+
+    let mut sections = Vec::new();
+    for (i, section) in enabled.iter().enumerate() {
+        let body = render_section_script(&tera, &ctx, config, section, i + 1, enabled.len())?;
+        check_embedded_configs(&body).map_err(|e| format!("Section '{}': {}", section.id, e))?;
+        sections.push(serde_json::json!({ "id": section.id, "body": body }));
+    }
+    ctx.insert("sections", &sections);
+    tera.render("main.sh.tera", &ctx).map_err(|e| format!("Render error: {}", error_chain(&e)))
+}
+
+/// Render the companion script that removes what `generate` installs.
+pub fn generate_uninstall(config: &GhosttyConfig, options: &UninstallOptions) -> Result<String, String> {
+    let tera = tera()?;
+    let mut ctx = build_context(config)?;
+    ctx.insert("options", options);
+    ctx.insert("uninstall_sections", &uninstall::script_sections(config));
+    tera.render("uninstall.sh.tera", &ctx).map_err(|e| format!("Render error: {}", error_chain(&e)))
+}
+
+/// Render one of the scripts shipped with an export; `vars` is available
+/// to the template as `export`.
+pub fn render_export_script(config: &GhosttyConfig, template_name: &str, vars: &serde_json::Value) -> Result<String, String> {
+    let tera = tera()?;
+    let mut ctx = build_context(config)?;
+    ctx.insert("export", vars);
+    tera.render(template_name, &ctx).map_err(|e| format!("Render error: {}", error_chain(&e)))
 }
 
 pub fn preview_section(config: &GhosttyConfig, section: &str) -> Result<String, String> {
-    let tera = create_tera()?;
+    let tera = tera()?;
     let ctx = build_context(config)?;
-    let template_name = format!("{}.sh.tera", section);
-    tera.render(&template_name, &ctx).map_err(|e| format!("Render error: {}", e))
+    // Numbered as in the full script, counting this section even when disabled
+    let shown: Vec<SectionInfo> = registry::list_sections()
+        .into_iter()
+        .filter(|s| s.id == section || registry::is_enabled(config, &s.id))
+        .collect();
+    let number = shown.iter().position(|s| s.id == section).ok_or_else(|| format!("Unknown section '{}'", section))?;
+    render_section_script(&tera, &ctx, config, &shown[number], number + 1, shown.len())
+}
+
+/// Render a section's script body with its progress number.
+fn render_section_script(
+    tera: &Tera,
+    ctx: &Context,
+    config: &GhosttyConfig,
+    section: &SectionInfo,
+    number: usize,
+    total: usize,
+) -> Result<String, String> {
+    let mut ctx = ctx.clone();
+    ctx.insert("section_number", &number);
+    ctx.insert("section_total", &total);
+    if let Some(manifest) = custom::registry().get(&section.id) {
+        ctx.insert("custom", &custom::render_section(config, manifest)?);
+    }
+    tera.render(&section.template, &ctx).map_err(|e| format!("Render error: {}", error_chain(&e)))
 }
 
 /// Render a config-only apply template (used by applier module).
 pub fn render_apply_template(config: &GhosttyConfig, template_name: &str) -> Result<String, String> {
-    let tera = create_tera()?;
+    let tera = tera()?;
     let ctx = build_context(config)?;
     tera.render(template_name, &ctx).map_err(|e| format!("Render error: {}", e))
 }
 
+/// Syntax a rendered config file must parse as before it is written.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Syntax {
+    Toml,
+    Json,
+    /// JSON with comments and trailing commas, as fastfetch reads it.
+    Jsonc,
+    Yaml,
+}
+
+impl Syntax {
+    /// The syntax of a config file, by extension.
+    pub fn from_path(path: &str) -> Option<Syntax> {
+        match path.rsplit_once('.')?.1 {
+            "toml" => Some(Syntax::Toml),
+            "json" => Some(Syntax::Json),
+            "jsonc" => Some(Syntax::Jsonc),
+            "yml" | "yaml" => Some(Syntax::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// Parse `content` with a real parser for its format, so a value that slips
+/// past the escaping filters fails the apply instead of breaking the tool.
+pub fn check_syntax(content: &str, syntax: Syntax) -> Result<(), String> {
+    match syntax {
+        Syntax::Toml => content.parse::<toml::Table>().map(|_| ()).map_err(|e| {
+            let line = e.span().map(|span| content[..span.start].lines().count().max(1)).unwrap_or(1);
+            format!("invalid TOML (line {}): {}", line, e.message())
+        }),
+        Syntax::Json => serde_json::from_str::<serde_json::Value>(content).map(|_| ()).map_err(|e| format!("invalid JSON: {}", e)),
+        Syntax::Jsonc => serde_json::from_str::<serde_json::Value>(&strip_jsonc(content))
+            .map(|_| ())
+            .map_err(|e| format!("invalid JSONC: {}", e)),
+        Syntax::Yaml => yaml_rust2::YamlLoader::load_from_str(content).map(|_| ()).map_err(|e| format!("invalid YAML: {}", e)),
+    }
+}
+
+/// Drop the comments and trailing commas JSONC allows, keeping line breaks
+/// so parse errors still point at the right line.
+fn strip_jsonc(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('/', Some('/')) => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push(c);
+                    }
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            ('}' | ']', _) => {
+                let kept = out.trim_end().len();
+                if out[..kept].ends_with(',') {
+                    out.remove(kept - 1);
+                }
+                out.push(c);
+            }
+            _ => {
+                in_string = c == '"';
+                out.push(c);
+            }
+        }
+    }
+    out
+}
+
+/// Check the config files a section script writes with a quoted heredoc
+/// (`cat > "<path>" << 'TAG'`), by the target's extension.
+fn check_embedded_configs(script: &str) -> Result<(), String> {
+    let mut lines = script.lines();
+    while let Some(line) = lines.next() {
+        let Some((target, tag)) = line.trim().strip_prefix("cat > ").and_then(|rest| rest.split_once(" << ")) else {
+            continue;
+        };
+        let Some(tag) = tag.trim().strip_prefix('\'').and_then(|t| t.strip_suffix('\'')) else { continue };
+        let body: Vec<&str> = lines.by_ref().take_while(|l| *l != tag).collect();
+        let target = target.trim_matches('"');
+        if let Some(syntax) = Syntax::from_path(target) {
+            check_syntax(&body.join("\n"), syntax).map_err(|e| format!("{} would be written with {}", target, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Render an apply template and check that the result parses as `syntax`.
+pub fn render_checked(config: &GhosttyConfig, template_name: &str, syntax: Syntax) -> Result<String, String> {
+    let content = render_apply_template(config, template_name)?;
+    check_syntax(&content, syntax).map_err(|e| format!("{} rendered {}", template_name, e))?;
+    Ok(content)
+}
+
 fn get_all_templates() -> Vec<(&'static str, &'static str)> {
     vec![
         ("main.sh.tera", include_str!("templates/main.sh.tera")),
         ("header.sh.tera", include_str!("templates/header.sh.tera")),
+        ("helpers.sh.tera", include_str!("templates/helpers.sh.tera")),
         ("clean_artifacts.sh.tera", include_str!("templates/clean_artifacts.sh.tera")),
         ("packages.sh.tera", include_str!("templates/packages.sh.tera")),
         ("ghostty.sh.tera", include_str!("templates/ghostty.sh.tera")),
@@ -75,7 +427,15 @@ fn get_all_templates() -> Vec<(&'static str, &'static str)> {
         ("themes.sh.tera", include_str!("templates/themes.sh.tera")),
         ("tmux_scripts.sh.tera", include_str!("templates/tmux_scripts.sh.tera")),
         ("zshrc.sh.tera", include_str!("templates/zshrc.sh.tera")),
+        ("custom_section.sh.tera", include_str!("templates/custom_section.sh.tera")),
         ("done.sh.tera", include_str!("templates/done.sh.tera")),
+        ("uninstall.sh.tera", include_str!("templates/uninstall.sh.tera")),
+        ("export_install.sh.tera", include_str!("templates/export_install.sh.tera")),
+        ("chezmoi_modify_block.sh.tera", include_str!("templates/chezmoi_modify_block.sh.tera")),
+        ("chezmoi_packages.sh.tera", include_str!("templates/chezmoi_packages.sh.tera")),
+        ("chezmoi_git_include.sh.tera", include_str!("templates/chezmoi_git_include.sh.tera")),
+        ("stow_install.sh.tera", include_str!("templates/stow_install.sh.tera")),
+        ("home_manager.nix.tera", include_str!("templates/home_manager.nix.tera")),
     ]
 }
 
@@ -83,6 +443,13 @@ fn get_apply_templates() -> Vec<(&'static str, &'static str)> {
     vec![
         ("apply/ghostty_config.tera", include_str!("templates/apply/ghostty_config.tera")),
         ("apply/ghostty_theme.tera", include_str!("templates/apply/ghostty_theme.tera")),
+        ("apply/ghostty_base.tera", include_str!("templates/apply/ghostty_base.tera")),
+        ("apply/ghostty_fonts.tera", include_str!("templates/apply/ghostty_fonts.tera")),
+        ("apply/ghostty_appearance.tera", include_str!("templates/apply/ghostty_appearance.tera")),
+        ("apply/ghostty_keybinds.tera", include_str!("templates/apply/ghostty_keybinds.tera")),
+        ("apply/ghostty_extra.tera", include_str!("templates/apply/ghostty_extra.tera")),
+        ("apply/ghostty_modular.tera", include_str!("templates/apply/ghostty_modular.tera")),
+        ("apply/ghostty_local.tera", include_str!("templates/apply/ghostty_local.tera")),
         ("apply/starship_config.tera", include_str!("templates/apply/starship_config.tera")),
         ("apply/tmux_config.tera", include_str!("templates/apply/tmux_config.tera")),
         ("apply/neovim_config.tera", include_str!("templates/apply/neovim_config.tera")),
@@ -96,3 +463,251 @@ fn get_apply_templates() -> Vec<(&'static str, &'static str)> {
         ("apply/zshrc_config.tera", include_str!("templates/apply/zshrc_config.tera")),
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_must_name_a_section_the_script_runs() {
+        let mut config = GhosttyConfig::default();
+        config.neovim.enabled = false;
+        let from = |id: &str| SectionSelection { from: Some(id.into()), ..Default::default() };
+        assert!(generate(&config, &from("neovim")).is_err());
+        assert!(generate(&config, &from("nope")).is_err());
+
+        let script = generate(&config, &from("tmux")).unwrap();
+        let order = script.lines().find(|l| l.starts_with("SECTION_ORDER=(")).unwrap();
+        assert!(order.contains(" tmux ") && !order.contains("neovim"));
+    }
+
+    #[test]
+    fn sections_are_numbered_among_the_ones_the_script_runs() {
+        let mut config = GhosttyConfig::default();
+        config.git.enabled = false;
+        config.neovim.enabled = false;
+        let script = generate(&config, &SectionSelection::default()).unwrap();
+        let progress: Vec<(Option<u32>, Option<u32>)> = script
+            .lines()
+            .filter_map(|l| l.strip_prefix("print_header \"")?.strip_suffix('"'))
+            .filter_map(crate::executor::parser::parse_line)
+            .map(|e| (e.section, e.total_sections))
+            .collect();
+        let total = registry::enabled_sections(&config).len() as u32;
+        let expected: Vec<(Option<u32>, Option<u32>)> = (1..=total).map(|n| (Some(n), Some(total))).collect();
+        assert_eq!(progress, expected);
+
+        // A disabled section previews with the number it would get
+        let git = registry::list_sections().iter().position(|s| s.id == "git").unwrap();
+        let before = registry::list_sections()[..git].iter().filter(|s| registry::is_enabled(&config, &s.id)).count();
+        let preview = preview_section(&config, "git").unwrap();
+        assert!(preview.contains(&format!("\"[{}/{}] — ", before + 1, total + 1)), "{}", preview);
+    }
+
+    #[test]
+    fn sections_are_only_recorded_when_they_did_not_warn() {
+        let config = GhosttyConfig::default();
+        let script = generate(&config, &SectionSelection::default()).unwrap();
+        let sections = registry::enabled_sections(&config);
+        for section in &sections {
+            assert!(script.contains(&format!("finish_section {} \"$section_warnings\"", section.id)));
+        }
+        assert_eq!(script.matches("section_warnings=$warn_count").count(), sections.len());
+        assert!(!script.contains("mark_done"));
+    }
+
+    /// Run the script's section-selection block with `args` against the state
+    /// file under `state_home`; sections in `warned` print a warning.
+    fn run_selection(script: &str, state_home: &std::path::Path, args: &[&str], warned: &str) -> Vec<String> {
+        let start = script.find("# ── Section selection").unwrap();
+        let end = script.find("# ── Helpers").unwrap();
+        let program = format!(
+            "print_skip() {{ :; }}; print_error() {{ echo \"$1\" >&2; }}; warn_count=0\n{}\n\
+             for id in \"${{SECTION_ORDER[@]}}\"; do if should_run $id; then w=$warn_count; \
+             [[ ' {} ' == *\" $id \"* ]] && ((warn_count++)); finish_section $id $w; fi; done",
+            &script[start..end],
+            warned
+        );
+        let output = std::process::Command::new("bash")
+            .args(["-c", &program, "script"])
+            .args(args)
+            .env("XDG_STATE_HOME", state_home)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let state = std::fs::read_to_string(state_home.join("ghostty-ultimate/completed-sections")).unwrap();
+        state.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn partial_runs_keep_the_progress_resume_needs() {
+        let state_home = std::env::temp_dir().join(format!("ghostty-ultimate-state-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&state_home);
+        let script = generate(&GhosttyConfig::default(), &SectionSelection::default()).unwrap();
+        let all: Vec<String> = registry::enabled_sections(&GhosttyConfig::default()).into_iter().map(|s| s.id).collect();
+
+        let done = run_selection(&script, &state_home, &[], "git");
+        assert_eq!(done.len(), all.len() - 1);
+        assert!(!done.contains(&"git".to_string()));
+
+        // A partial run neither truncates the record nor keeps a section that warned again
+        let done = run_selection(&script, &state_home, &["--only", "git,tmux"], "tmux");
+        assert_eq!(done.len(), all.len() - 1);
+        assert!(done.contains(&"git".to_string()) && !done.contains(&"tmux".to_string()));
+
+        let done = run_selection(&script, &state_home, &["--resume"], "");
+        assert_eq!(done.len(), all.len());
+        // Only a full run starts over
+        let done = run_selection(&script, &state_home, &[], "zshrc");
+        assert_eq!(done.len(), all.len() - 1);
+        let _ = std::fs::remove_dir_all(&state_home);
+    }
+
+    #[test]
+    fn jsonc_allows_comments_and_trailing_commas() {
+        let text = "{\n  // the logo\n  \"url\": \"https://x//y\", /* a, */\n  \"list\": [1, 2,],\n}\n";
+        assert!(check_syntax(text, Syntax::Json).is_err());
+        assert!(check_syntax(text, Syntax::Jsonc).is_ok());
+        assert_eq!(strip_jsonc("[\"a,]\",\n]"), "[\"a,]\"\n]");
+        let error = check_syntax("{\n/* one\ntwo */\n\"a\": }", Syntax::Jsonc).unwrap_err();
+        assert!(error.contains("line 4"), "{}", error);
+    }
+
+    #[test]
+    fn disabling_the_last_fastfetch_module_leaves_valid_json() {
+        let mut config = GhosttyConfig::default();
+        config.fastfetch.modules.last_mut().unwrap().enabled = false;
+        let content = render_checked(&config, "apply/fastfetch_config.tera", Syntax::Json).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+        let enabled = config.fastfetch.modules.iter().filter(|m| m.enabled).count();
+        assert_eq!(json["modules"].as_array().unwrap().len(), enabled);
+    }
+
+    #[test]
+    fn embedded_configs_are_parse_checked() {
+        let script = "cat > \"$HOME/.config/a.toml\" << 'A'\nkey = \"ok\"\nA\n  cat > \"$DIR/b.yml\" << 'B'\nkey: [\nB\n";
+        let error = check_embedded_configs(script).unwrap_err();
+        assert!(error.starts_with("$DIR/b.yml would be written with invalid YAML"), "{}", error);
+        assert!(check_embedded_configs("cat > \"$HOME/notes\" << 'N'\n{ not config\nN\n").is_ok());
+
+        let script = generate(&GhosttyConfig::default(), &SectionSelection::default()).unwrap();
+        assert!(script.contains("cat > \"$FF_DIR/config.jsonc\" << 'FF'"));
+    }
+
+    #[test]
+    fn script_writes_identity_includes_after_signing() {
+        let mut config = GhosttyConfig::default();
+        config.git.signing.enabled = true;
+        config.git.identities = vec![crate::config::sections::GitIdentity {
+            id: "work".into(),
+            enabled: true,
+            user_name: "Work Name".into(),
+            email: "me@work.example".into(),
+            signing_key: "~/.ssh/work.pub".into(),
+            directories: vec!["~/work".into(), "~/clients/".into()],
+        }];
+        let script = preview_section(&config, "git").unwrap();
+        let position = |needle: &str| script.rfind(needle).unwrap_or_else(|| panic!("{} missing", needle));
+        let last_setting = position("managed_git 'user.signingkey'").max(position("managed_git 'gpg.format'"));
+        let first_include = script.find("managed_git 'includeIf.").unwrap();
+        assert!(first_include > last_setting);
+        assert!(first_include > position("managed_git 'alias."));
+        assert_eq!(script.matches("managed_git 'includeIf.").count(), 2);
+    }
+
+    #[test]
+    fn overrides_that_fail_to_compile_fall_back_to_the_builtin() {
+        let builtins = [("apply/x.tera", "built-in {{ v }}".to_string()), ("apply/y.tera", "y".to_string())];
+        let overrides = [
+            ("apply/x.tera".to_string(), "{% if v %}unclosed".to_string()),
+            ("apply/y.tera".to_string(), r#"{% extends "builtin/apply/y.tera" %}"#.to_string()),
+            ("partial.tera".to_string(), "{{ broken".to_string()),
+        ];
+        let (tera, errors) = create_tera(&builtins, &overrides, &[]).unwrap();
+        let failed: Vec<&str> = errors.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(failed, ["apply/x.tera", "partial.tera"]);
+
+        let mut ctx = Context::new();
+        ctx.insert("v", &1);
+        assert_eq!(tera.render("apply/x.tera", &ctx).unwrap(), "built-in 1");
+        assert_eq!(tera.render("apply/y.tera", &ctx).unwrap(), "y");
+        assert!(!tera.get_template_names().any(|name| name == "partial.tera"));
+    }
+
+    #[test]
+    fn every_modeled_ghostty_option_is_written_before_the_extras() {
+        // Fields that are not Ghostty keys, or are written under another name
+        const NOT_OPTIONS: [&str; 10] = [
+            "enabled", "layout", "keybinds", "shaders", "extra_options", "custom_shader_enabled",
+            "custom_shader_path", "install_community_shaders", "font_features", "font_variation",
+        ];
+        let mut config = GhosttyConfig::default();
+        // Optional options are only written once set
+        let mut ghostty = serde_json::to_value(&config.ghostty).unwrap();
+        for value in ghostty.as_object_mut().unwrap().values_mut() {
+            if value.as_str() == Some("") {
+                *value = "set".into();
+            }
+        }
+        config.ghostty = serde_json::from_value(ghostty.clone()).unwrap();
+        config.ghostty.window_opacity = 0.9;
+        config.ghostty.extra_options = ["window-save-state", "", "custom-shader-animation"]
+            .iter()
+            .map(|key| crate::config::sections::GhosttyOption { key: key.to_string(), value: "always\nnever".into() })
+            .collect();
+
+        let rendered = render_apply_template(&config, "apply/ghostty_config.tera").unwrap();
+        let keys: Vec<&str> = rendered
+            .lines()
+            .filter(|l| !l.starts_with('#'))
+            .filter_map(|l| l.split_once('=').map(|(key, _)| key.trim()))
+            .collect();
+        for field in ghostty.as_object().unwrap().keys().filter(|f| !NOT_OPTIONS.contains(&f.as_str())) {
+            assert!(keys.contains(&field.replace('_', "-").as_str()), "{} is not written", field);
+        }
+        assert_eq!(keys.iter().filter(|k| **k == "font-feature").count(), config.ghostty.font_features.len());
+        let tail: Vec<&str> = rendered.trim_end().lines().rev().take(3).collect();
+        assert_eq!(
+            tail,
+            ["custom-shader-animation = always never", "window-save-state = always never", "# ── Extra options (passed through verbatim) ──────────────────────"]
+        );
+    }
+
+    #[test]
+    fn the_template_cache_is_rebuilt_when_a_source_changes() {
+        let dir = std::env::temp_dir().join(format!("ghostty-ultimate-cache-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let write = |name: &str, content: &str, age: u64| {
+            let path = dir.join(name);
+            fs::write(&path, content).unwrap();
+            let modified = SystemTime::now() - std::time::Duration::from_secs(age);
+            fs::File::options().write(true).open(&path).unwrap().set_modified(modified).unwrap();
+            path
+        };
+        let name = get_apply_templates()[0].0.to_string();
+        let overrides = [(name.clone(), write("override.tera", "one", 60))];
+        let custom = [("custom/x.tera".to_string(), write("custom.tera", "{{ broken", 60))];
+        let cache = Mutex::new(None);
+        let render = |c: &CompiledTemplates| c.tera.render(&name, &Context::new()).unwrap();
+
+        let first = compile_cached(&cache, None, &overrides, &custom).unwrap();
+        assert_eq!(render(&first), "one");
+        assert_eq!(first.template_errors[0].0, "custom/x.tera");
+        assert!(Arc::ptr_eq(&first, &compile_cached(&cache, None, &overrides, &custom).unwrap()));
+
+        write("override.tera", "two", 30);
+        let second = compile_cached(&cache, None, &overrides, &custom).unwrap();
+        assert!(!Arc::ptr_eq(&first, &second));
+        assert_eq!(render(&second), "two");
+
+        write("custom.tera", "fixed", 30);
+        let third = compile_cached(&cache, None, &overrides, &custom).unwrap();
+        assert!(third.template_errors.is_empty());
+        assert_eq!(third.tera.render("custom/x.tera", &Context::new()).unwrap(), "fixed");
+
+        fs::remove_file(dir.join("custom.tera")).unwrap();
+        let fourth = compile_cached(&cache, None, &overrides, &custom).unwrap();
+        assert!(!fourth.tera.get_template_names().any(|n| n == "custom/x.tera"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
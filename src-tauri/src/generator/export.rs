@@ -0,0 +1,671 @@
+use crate::config::schema::GhosttyConfig;
+use crate::config::sections::{GhosttyLayout, GitApplyMode};
+use crate::executor::sandbox::make_executable;
+use super::applier::{GHOSTTY_INCLUDES, MANAGED_GITCONFIG, ZSHRC_END_MARKER, ZSHRC_START_MARKER};
+use super::custom;
+use super::custom::HEREDOC_DELIMITER;
+use super::engine::{self, render_apply_template, render_checked, Syntax};
+use super::home_manager;
+use super::registry;
+use super::uninstall::{install_manifest, Artifact};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directory in an export that mirrors `$HOME`.
+pub const HOME_DIR: &str = "home";
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+pub const INSTALLER_FILE: &str = "install.sh";
+
+/// The Ghostty overrides file, which belongs to the user once it exists.
+pub(crate) const GHOSTTY_LOCAL: &str = ".config/ghostty/local";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// A directory: `home/`, the manifest and the installer.
+    #[default]
+    Tree,
+    /// The same tree as a `.tar.gz`.
+    Tarball,
+    /// A chezmoi source directory.
+    Chezmoi,
+    /// One GNU stow package per tool.
+    Stow,
+    /// A Nix home-manager module.
+    HomeManager,
+}
+
+/// How an exported file is installed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ExportKind {
+    /// Copied over the file in the home directory.
+    File,
+    /// A marked block merged into a file the user also edits, replacing
+    /// the lines from `start` to `end` or appended when there are none.
+    Block { start: String, end: String },
+}
+
+/// One rendered file, relative to the home directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportFile {
+    pub section: String,
+    pub path: String,
+    #[serde(flatten)]
+    pub kind: ExportKind,
+    #[serde(skip)]
+    pub content: String,
+}
+
+/// Written next to `home/` so other tools know what the tree holds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportManifest {
+    pub theme: String,
+    pub sections: Vec<String>,
+    pub files: Vec<ExportFile>,
+    /// Path the global gitconfig should include, when git is exported.
+    #[serde(default)]
+    pub git_include: Option<String>,
+    /// Homebrew formulae and casks the setup would install.
+    #[serde(default)]
+    pub packages: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportReport {
+    pub path: String,
+    pub files: Vec<String>,
+}
+
+// ── Rendering ───────────────────────────────────────────────────────
+
+/// The files one enabled section writes, as (path, content). Mirrors the
+/// applier, without anything that depends on the machine: bat's config
+/// directory is always `~/.config/bat`, and the allowed signers file, which
+/// reads the local public key, is left out.
+fn section_files(config: &GhosttyConfig, section: &str) -> Result<Vec<(String, String)>, String> {
+    let render = |path: &str, template: &str| -> Result<(String, String), String> {
+        Ok((path.to_string(), render_apply_template(config, template)?))
+    };
+    let checked = |path: &str, template: &str, syntax| -> Result<(String, String), String> {
+        Ok((path.to_string(), render_checked(config, template, syntax)?))
+    };
+
+    match section {
+        "ghostty" => {
+            let mut files = vec![render(&format!(".config/ghostty/themes/{}", config.theme_name()), "apply/ghostty_theme.tera")?];
+            if config.ghostty.layout == GhosttyLayout::Single {
+                files.push(render(".config/ghostty/config", "apply/ghostty_config.tera")?);
+                return Ok(files);
+            }
+            files.push(render(".config/ghostty/config", "apply/ghostty_modular.tera")?);
+            for (name, template) in GHOSTTY_INCLUDES {
+                files.push(render(&format!(".config/ghostty/{}", name), template)?);
+            }
+            files.push(render(GHOSTTY_LOCAL, "apply/ghostty_local.tera")?);
+            Ok(files)
+        }
+        "starship" => Ok(vec![checked(".config/starship.toml", "apply/starship_config.tera", Syntax::Toml)?]),
+        "tmux" => Ok(vec![render(".tmux.conf", "apply/tmux_config.tera")?]),
+        "neovim" => Ok(vec![render(".config/nvim/init.lua", "apply/neovim_config.tera")?]),
+        "tool_configs" => Ok(vec![
+            checked(".config/atuin/config.toml", "apply/atuin_config.tera", Syntax::Toml)?,
+            checked("Library/Application Support/lazygit/config.yml", "apply/lazygit_config.tera", Syntax::Yaml)?,
+        ]),
+        "git" => {
+            let git = &config.git;
+            if git.apply_mode == GitApplyMode::Remove {
+                return Ok(Vec::new());
+            }
+            let mut files = vec![(MANAGED_GITCONFIG.to_string(), gitconfig_text(&git.managed_settings(&config.palette)))];
+            for identity in git.identities.iter().filter(|i| i.enabled) {
+                if !identity.has_valid_id() {
+                    return Err(format!("Invalid git identity id '{}'", identity.id));
+                }
+                files.push((identity.include_file(), gitconfig_text(&identity.settings())));
+            }
+            Ok(files)
+        }
+        "themes" => {
+            let themes = &config.themes;
+            let mut files = Vec::new();
+            if themes.bat_theme_enabled {
+                files.push(render(".config/bat/themes/Ayu Dark.tmTheme", "apply/bat_theme.tera")?);
+            }
+            files.push(render(".config/btop/btop.conf", "apply/btop_config.tera")?);
+            if themes.yazi_theme_enabled {
+                files.push(checked(".config/yazi/theme.toml", "apply/yazi_theme.tera", Syntax::Toml)?);
+            }
+            Ok(files)
+        }
+        "fastfetch" => Ok(vec![
+            (".config/fastfetch/quotes.txt".to_string(), config.fastfetch.quotes.join("\n")),
+            checked(".config/fastfetch/config.jsonc", "apply/fastfetch_config.tera", Syntax::Jsonc)?,
+        ]),
+        "zsh_plugins" => Ok(vec![checked(".config/yazi/yazi.toml", "apply/yazi_config.tera", Syntax::Toml)?]),
+        "zshrc" => Ok(vec![render(".zshrc", "apply/zshrc_config.tera")?]),
+        id => match custom::registry().get(id) {
+            Some(manifest) => Ok(custom::render_files(config, manifest)?.into_iter().map(|f| (f.target, f.content)).collect()),
+            // Packages, cleanup and the tmux scripts only exist as script steps
+            None => Ok(Vec::new()),
+        },
+    }
+}
+
+/// Every file the enabled sections write, in script order.
+pub fn rendered_files(config: &GhosttyConfig) -> Result<Vec<ExportFile>, String> {
+    let mut files = Vec::new();
+    for section in registry::enabled_sections(config) {
+        for (path, content) in section_files(config, &section.id)? {
+            let kind = if section.id == "zshrc" {
+                ExportKind::Block { start: ZSHRC_START_MARKER.into(), end: ZSHRC_END_MARKER.into() }
+            } else {
+                ExportKind::File
+            };
+            files.push(ExportFile { section: section.id.clone(), path, kind, content });
+        }
+    }
+    Ok(files)
+}
+
+/// Settings as gitconfig text. Keys are `section.name` or
+/// `section.subsection.name`, where the subsection may itself contain dots.
+pub(crate) fn gitconfig_text(settings: &[(String, String)]) -> String {
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+    for (key, value) in settings {
+        let Some((head, name)) = key.rsplit_once('.') else { continue };
+        let header = match head.split_once('.') {
+            Some((section, subsection)) => {
+                format!("[{} \"{}\"]", section, subsection.replace('\\', "\\\\").replace('"', "\\\""))
+            }
+            None => format!("[{}]", head),
+        };
+        let line = format!("\t{} = {}", name, gitconfig_value(value));
+        match groups.iter_mut().find(|(h, _)| *h == header) {
+            Some((_, lines)) => lines.push(line),
+            None => groups.push((header, vec![line])),
+        }
+    }
+    groups.into_iter().map(|(header, lines)| format!("{}\n{}\n", header, lines.join("\n"))).collect()
+}
+
+/// Quote a value when git would otherwise trim it or read a comment.
+fn gitconfig_value(value: &str) -> String {
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n").replace('\t', "\\t");
+    let needs_quotes = value.starts_with(char::is_whitespace)
+        || value.ends_with(char::is_whitespace)
+        || value.contains(['#', ';']);
+    if needs_quotes { format!("\"{}\"", escaped) } else { escaped }
+}
+
+/// Homebrew formulae and casks the enabled packages section installs.
+pub(crate) fn brew_packages(config: &GhosttyConfig) -> Vec<(String, bool)> {
+    install_manifest(config)
+        .into_iter()
+        .filter_map(|entry| match entry.artifact {
+            Artifact::Package { name, cask } => Some((name, cask)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn git_include(files: &[ExportFile]) -> Option<String> {
+    files.iter().any(|f| f.path == MANAGED_GITCONFIG).then(|| format!("~/{}", MANAGED_GITCONFIG))
+}
+
+pub fn manifest(config: &GhosttyConfig, files: &[ExportFile]) -> ExportManifest {
+    let mut sections: Vec<String> = Vec::new();
+    for file in files {
+        if !sections.contains(&file.section) {
+            sections.push(file.section.clone());
+        }
+    }
+    ExportManifest {
+        theme: config.theme_name(),
+        sections,
+        files: files.to_vec(),
+        git_include: git_include(files),
+        packages: brew_packages(config).into_iter().map(|(name, _)| name).collect(),
+    }
+}
+
+/// Manifest sections with their files, for the installer template.
+pub fn script_sections(files: &[ExportFile]) -> Vec<serde_json::Value> {
+    let labels = registry::list_sections();
+    let mut ids: Vec<&str> = Vec::new();
+    for file in files {
+        if !ids.contains(&file.section.as_str()) {
+            ids.push(&file.section);
+        }
+    }
+    ids.iter()
+        .map(|id| {
+            let label = labels.iter().find(|s| s.id == *id).map_or(*id, |s| s.label.as_str());
+            let section_files: Vec<&ExportFile> = files.iter().filter(|f| f.section == *id).collect();
+            serde_json::json!({ "id": id, "label": label, "files": section_files })
+        })
+        .collect()
+}
+
+// ── Writing ─────────────────────────────────────────────────────────
+
+fn write_file(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn write_script(path: &Path, content: &str) -> Result<(), String> {
+    write_file(path, content)?;
+    make_executable(path)
+}
+
+/// Write `home/`, the manifest and the installer into `dir`.
+fn write_tree(config: &GhosttyConfig, dir: &Path) -> Result<Vec<String>, String> {
+    let files = rendered_files(config)?;
+    for file in &files {
+        write_file(&dir.join(HOME_DIR).join(&file.path), &file.content)?;
+    }
+
+    let manifest = serde_json::to_string_pretty(&manifest(config, &files)).map_err(|e| e.to_string())?;
+    write_file(&dir.join(MANIFEST_FILE), &(manifest + "\n"))?;
+
+    let vars = serde_json::json!({ "sections": script_sections(&files), "git_include": git_include(&files) });
+    write_script(&dir.join(INSTALLER_FILE), &engine::render_export_script(config, "export_install.sh.tera", &vars)?)?;
+    Ok(files.into_iter().map(|f| f.path).collect())
+}
+
+/// Export the rendered configs to `path`: a new (or empty) directory, or
+/// the archive file for a tarball. The home-manager module is a single file
+/// and may go into an existing directory, such as a Nix config checkout.
+pub fn export(config: &GhosttyConfig, path: &Path, format: ExportFormat) -> Result<ExportReport, String> {
+    let single_file = matches!(format, ExportFormat::Tarball | ExportFormat::HomeManager);
+    if !single_file && fs::read_dir(path).is_ok_and(|mut entries| entries.next().is_some()) {
+        return Err(format!("Export directory {} is not empty", path.display()));
+    }
+    let files = match format {
+        ExportFormat::Tree => write_tree(config, path)?,
+        ExportFormat::Tarball => write_tarball(config, path)?,
+        ExportFormat::Chezmoi => write_chezmoi(config, path)?,
+        ExportFormat::Stow => write_stow(config, path)?,
+        ExportFormat::HomeManager => write_home_manager(config, path)?,
+    };
+    Ok(ExportReport { path: path.display().to_string(), files })
+}
+
+/// Stage the tree in a temp directory and pack it with `tar`. The archive
+/// holds one top-level directory named after the file.
+fn write_tarball(config: &GhosttyConfig, path: &Path) -> Result<Vec<String>, String> {
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let name = file_name.trim_end_matches(".gz").trim_end_matches(".tar").trim_end_matches(".tgz");
+    let name = if name.is_empty() { "dotfiles" } else { name };
+
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let staging = std::env::temp_dir().join(format!("ghostty-ultimate-export-{}-{}", std::process::id(), nanos));
+    let result = write_tree(config, &staging.join(name)).and_then(|files| {
+        let output = Command::new("tar")
+            .arg("-czf")
+            .arg(absolute(path)?)
+            .arg("-C")
+            .arg(&staging)
+            .arg(name)
+            .output()
+            .map_err(|e| format!("Failed to run tar: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("tar failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        Ok(files)
+    });
+    let _ = fs::remove_dir_all(&staging);
+    result
+}
+
+fn absolute(path: &Path) -> Result<PathBuf, String> {
+    if path.is_absolute() {
+        return Ok(path.to_path_buf());
+    }
+    std::env::current_dir().map(|dir| dir.join(path)).map_err(|e| format!("Cannot resolve {}: {}", path.display(), e))
+}
+
+// ── chezmoi ─────────────────────────────────────────────────────────
+
+/// Source state attributes chezmoi reads from the start of a name.
+const CHEZMOI_PREFIXES: [&str; 17] = [
+    "after_", "before_", "create_", "dot_", "empty_", "encrypted_", "exact_", "executable_", "literal_",
+    "modify_", "once_", "onchange_", "private_", "readonly_", "remove_", "run_", "symlink_",
+];
+
+/// Source state name for one path component: `.foo` becomes `dot_foo`, and
+/// names chezmoi would read as attributes or templates are kept literal.
+fn chezmoi_name(name: &str) -> String {
+    let name = match name.strip_prefix('.') {
+        Some(rest) => format!("dot_{}", rest),
+        None if CHEZMOI_PREFIXES.iter().any(|p| name.starts_with(p)) => format!("literal_{}", name),
+        None => name.to_string(),
+    };
+    if name.ends_with(".tmpl") || name.ends_with(".literal") {
+        format!("{}.literal", name)
+    } else {
+        name
+    }
+}
+
+/// Source state path for a path relative to the home directory, with
+/// `attribute` (e.g. `modify_`) added to the file name.
+fn chezmoi_path(path: &str, attribute: &str) -> String {
+    let mut parts: Vec<String> = path.split('/').map(chezmoi_name).collect();
+    if let Some(last) = parts.last_mut() {
+        last.insert_str(0, attribute);
+    }
+    parts.join("/")
+}
+
+/// Write a chezmoi source directory: plain files, a `modify_` script that
+/// merges the managed block into `.zshrc`, `create_` for files the user owns
+/// once they exist, and `run_once_` scripts for packages and the git include.
+fn write_chezmoi(config: &GhosttyConfig, dir: &Path) -> Result<Vec<String>, String> {
+    let files = rendered_files(config)?;
+    for file in &files {
+        match &file.kind {
+            ExportKind::File => {
+                let attribute = if file.path == GHOSTTY_LOCAL { "create_" } else { "" };
+                write_file(&dir.join(chezmoi_path(&file.path, attribute)), &file.content)?;
+            }
+            ExportKind::Block { start, end } => {
+                if file.content.lines().any(|l| l == HEREDOC_DELIMITER) {
+                    return Err(format!("{} contains the line {}", file.path, HEREDOC_DELIMITER));
+                }
+                let vars = serde_json::json!({
+                    "block": file.content.trim_end_matches('\n'),
+                    "start": start,
+                    "end": end,
+                    "delimiter": HEREDOC_DELIMITER,
+                });
+                let script = engine::render_export_script(config, "chezmoi_modify_block.sh.tera", &vars)?;
+                write_script(&dir.join(chezmoi_path(&file.path, "modify_")), &script)?;
+            }
+        }
+    }
+
+    let packages = brew_packages(config);
+    if !packages.is_empty() {
+        let packages: Vec<serde_json::Value> =
+            packages.into_iter().map(|(name, cask)| serde_json::json!({ "name": name, "cask": cask })).collect();
+        let vars = serde_json::json!({ "packages": packages });
+        let script = engine::render_export_script(config, "chezmoi_packages.sh.tera", &vars)?;
+        write_script(&dir.join("run_once_before_10-install-packages.sh"), &script)?;
+    }
+    if let Some(include) = git_include(&files) {
+        let vars = serde_json::json!({ "git_include": include });
+        let script = engine::render_export_script(config, "chezmoi_git_include.sh.tera", &vars)?;
+        write_script(&dir.join("run_once_after_20-git-include.sh"), &script)?;
+    }
+    Ok(files.into_iter().map(|f| f.path).collect())
+}
+
+// ── GNU stow ────────────────────────────────────────────────────────
+
+/// Where the stow layout puts the managed zsh block. Stow links whole
+/// files, so `.zshrc` stays the user's and sources this one.
+const STOW_ZSH_FILE: &str = ".config/ghostty-ultimate/zshrc.zsh";
+
+/// Stow package for a file: the tool's directory under `.config` or
+/// `Library/Application Support`, or the dotfile's own name
+/// (`.tmux.conf` → `tmux`). Custom sections get one package each.
+fn stow_package(file: &ExportFile) -> String {
+    if matches!(file.kind, ExportKind::Block { .. }) {
+        return "zsh".to_string();
+    }
+    if custom::registry().get(&file.section).is_some() {
+        return file.section.clone();
+    }
+    let path = file
+        .path
+        .strip_prefix(".config/")
+        .or_else(|| file.path.strip_prefix("Library/Application Support/"))
+        .unwrap_or(&file.path);
+    let name = path.split('/').next().unwrap_or(path).trim_start_matches('.');
+    name.split('.').next().unwrap_or(name).to_string()
+}
+
+/// Write one stow package per tool and an `install.sh` that stows them,
+/// sources the zsh block from `.zshrc` and adds the git include.
+fn write_stow(config: &GhosttyConfig, dir: &Path) -> Result<Vec<String>, String> {
+    let files = rendered_files(config)?;
+    let mut packages: Vec<String> = Vec::new();
+    let mut zsh_source = None;
+    for file in &files {
+        let package = stow_package(file);
+        let path = match file.kind {
+            ExportKind::File => file.path.as_str(),
+            ExportKind::Block { .. } => {
+                zsh_source = Some(format!("~/{}", STOW_ZSH_FILE));
+                STOW_ZSH_FILE
+            }
+        };
+        write_file(&dir.join(&package).join(path), &file.content)?;
+        if !packages.contains(&package) {
+            packages.push(package);
+        }
+    }
+
+    let vars = serde_json::json!({
+        "stow_packages": packages,
+        "zsh_source": zsh_source,
+        "git_include": git_include(&files),
+    });
+    write_script(&dir.join(INSTALLER_FILE), &engine::render_export_script(config, "stow_install.sh.tera", &vars)?)?;
+    Ok(files.into_iter().map(|f| f.path).collect())
+}
+
+// ── home-manager ────────────────────────────────────────────────────
+
+/// Write the home-manager module into `dir`. Reports the files it
+/// configures, natively or as linked files.
+fn write_home_manager(config: &GhosttyConfig, dir: &Path) -> Result<Vec<String>, String> {
+    write_file(&dir.join(home_manager::MODULE_FILE), &home_manager::generate(config)?)?;
+    Ok(rendered_files(config)?.into_iter().map(|f| f.path).filter(|p| p != GHOSTTY_LOCAL).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::sections::GitIdentity;
+
+    fn work_identity() -> GitIdentity {
+        GitIdentity {
+            id: "work".into(),
+            enabled: true,
+            user_name: "Work Name".into(),
+            email: "me@work.example".into(),
+            signing_key: "~/.ssh/work.pub".into(),
+            directories: vec!["~/work".into(), "~/clients/".into()],
+        }
+    }
+
+    #[test]
+    fn identity_includes_come_after_the_signing_key() {
+        let mut config = GhosttyConfig::default();
+        config.git.signing.enabled = true;
+        config.git.identities = vec![work_identity()];
+        let files = section_files(&config, "git").unwrap();
+        let (_, managed) = files.iter().find(|(path, _)| path == MANAGED_GITCONFIG).unwrap();
+
+        let headers: Vec<&str> = managed.lines().filter(|l| l.starts_with('[')).collect();
+        let first = headers.iter().position(|h| h.starts_with("[includeIf")).unwrap();
+        assert_eq!(headers[first..], ["[includeIf \"gitdir:~/work/\"]", "[includeIf \"gitdir:~/clients/\"]"]);
+        assert!(headers[..first].contains(&"[gpg]") && headers[..first].contains(&"[user]"));
+
+        let identity = files.iter().find(|(path, _)| path == ".config/git/ghostty-ultimate-work.gitconfig").unwrap();
+        assert!(identity.1.contains("signingkey = ~/.ssh/work.pub"));
+    }
+
+    fn export_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ghostty-ultimate-export-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn gitconfig_values_are_quoted_when_git_would_misread_them() {
+        let settings = [
+            ("core.pager".to_string(), "delta".to_string()),
+            ("includeIf.gitdir:~/a.b/.path".to_string(), "~/x.gitconfig".to_string()),
+            ("alias.lg".to_string(), "log --grep=\"#1\"".to_string()),
+            ("core.pager".to_string(), " padded\t".to_string()),
+        ];
+        assert_eq!(
+            gitconfig_text(&settings),
+            "[core]\n\tpager = delta\n\tpager = \" padded\\t\"\n\
+             [includeIf \"gitdir:~/a.b/\"]\n\tpath = ~/x.gitconfig\n\
+             [alias]\n\tlg = \"log --grep=\\\"#1\\\"\"\n"
+        );
+    }
+
+    #[test]
+    fn the_tree_installer_copies_files_and_merges_the_zshrc_block() {
+        let base = export_dir("tree");
+        let out = base.join("out");
+        let config = GhosttyConfig::default();
+        let report = export(&config, &out, ExportFormat::Tree).unwrap();
+        let expected: Vec<String> = rendered_files(&config).unwrap().into_iter().map(|f| f.path).collect();
+        assert_eq!(report.files, expected);
+        assert!(export(&config, &out, ExportFormat::Tree).unwrap_err().contains("is not empty"));
+
+        let manifest: ExportManifest = serde_json::from_str(&fs::read_to_string(out.join(MANIFEST_FILE)).unwrap()).unwrap();
+        assert_eq!(manifest.git_include.as_deref(), Some(format!("~/{}", MANAGED_GITCONFIG).as_str()));
+        let zshrc = manifest.files.iter().find(|f| f.path == ".zshrc").unwrap();
+        assert!(matches!(zshrc.kind, ExportKind::Block { .. }));
+        assert!(manifest.files.iter().filter(|f| f.path != ".zshrc").all(|f| f.kind == ExportKind::File));
+
+        let home = base.join("home");
+        fs::create_dir_all(&home).unwrap();
+        fs::write(home.join(".zshrc"), "export FOO=1\n").unwrap();
+        let install = || {
+            let output = Command::new("bash")
+                .arg(out.join(INSTALLER_FILE))
+                .env("HOME", &home)
+                .env("GIT_CONFIG_GLOBAL", home.join(".gitconfig"))
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stdout));
+            String::from_utf8_lossy(&output.stdout).to_string()
+        };
+        install();
+        let zshrc = fs::read_to_string(home.join(".zshrc")).unwrap();
+        assert!(zshrc.starts_with("export FOO=1\n\n") && zshrc.contains(ZSHRC_START_MARKER), "{}", zshrc);
+        for path in &expected {
+            assert!(home.join(path).is_file(), "{}", path);
+        }
+        let backups = fs::read_dir(&home).unwrap().flatten().map(|e| e.file_name().to_string_lossy().to_string());
+        assert_eq!(backups.filter(|name| name.starts_with(".zshrc.bak.")).count(), 1);
+
+        let again = install();
+        assert!(again.contains("~/.zshrc (unchanged)") && again.contains("(already set)"), "{}", again);
+        assert_eq!(fs::read_to_string(home.join(".zshrc")).unwrap(), zshrc);
+        let gitconfig = fs::read_to_string(home.join(".gitconfig")).unwrap();
+        assert_eq!(gitconfig.matches(MANAGED_GITCONFIG).count(), 1, "{}", gitconfig);
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn the_tarball_holds_one_directory_named_after_the_file() {
+        let base = export_dir("tarball");
+        let archive = base.join("dots.tar.gz");
+        export(&GhosttyConfig::default(), &archive, ExportFormat::Tarball).unwrap();
+        let output = Command::new("tar").arg("-tzf").arg(&archive).output().unwrap();
+        let entries = String::from_utf8_lossy(&output.stdout).to_string();
+        assert!(entries.lines().all(|e| e.starts_with("dots/")), "{}", entries);
+        for entry in ["dots/install.sh", "dots/manifest.json", "dots/home/.zshrc", "dots/home/.config/ghostty/config"] {
+            assert!(entries.lines().any(|e| e == entry), "{}", entries);
+        }
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn chezmoi_names_keep_dotfiles_and_attribute_like_names_literal() {
+        assert_eq!(chezmoi_path(".config/ghostty/config", ""), "dot_config/ghostty/config");
+        assert_eq!(chezmoi_path(GHOSTTY_LOCAL, "create_"), "dot_config/ghostty/create_local");
+        assert_eq!(chezmoi_path(".zshrc", "modify_"), "modify_dot_zshrc");
+        assert_eq!(chezmoi_path(".config/run_me/empty_notes.tmpl", ""), "dot_config/literal_run_me/literal_empty_notes.tmpl.literal");
+        assert_eq!(
+            chezmoi_path("Library/Application Support/lazygit/config.yml", ""),
+            "Library/Application Support/lazygit/config.yml"
+        );
+    }
+
+    #[test]
+    fn the_chezmoi_modify_script_replaces_the_block_and_keeps_the_rest() {
+        let dir = export_dir("chezmoi");
+        let mut config = GhosttyConfig::default();
+        config.ghostty.layout = GhosttyLayout::Modular;
+        let files = export(&config, &dir, ExportFormat::Chezmoi).unwrap().files;
+        for path in files.iter().filter(|p| *p != ".zshrc" && *p != GHOSTTY_LOCAL) {
+            assert!(dir.join(chezmoi_path(path, "")).is_file(), "{}", path);
+        }
+        // The overrides file belongs to the user once chezmoi has created it
+        assert!(dir.join("dot_config/ghostty/create_local").is_file());
+        for script in ["run_once_before_10-install-packages.sh", "run_once_after_20-git-include.sh"] {
+            assert!(dir.join(script).is_file(), "{}", script);
+        }
+
+        let modify = |input: &str| {
+            let mut child = Command::new("bash")
+                .arg(dir.join("modify_dot_zshrc"))
+                .stdin(std::process::Stdio::piped())
+                .stdout(std::process::Stdio::piped())
+                .spawn()
+                .unwrap();
+            use std::io::Write;
+            child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+            let output = child.wait_with_output().unwrap();
+            assert!(output.status.success());
+            String::from_utf8(output.stdout).unwrap()
+        };
+        let block = rendered_files(&config).unwrap().into_iter().find(|f| f.path == ".zshrc").unwrap().content;
+        let stale = format!("export FOO=1\n{}\nold\n{}\nalias x=y\n", ZSHRC_START_MARKER, ZSHRC_END_MARKER);
+        let merged = modify(&stale);
+        assert_eq!(merged, format!("export FOO=1\n{}\nalias x=y\n", block.trim_end_matches('\n')));
+        assert_eq!(modify(&merged), merged);
+        assert_eq!(modify("export FOO=1\n"), format!("export FOO=1\n\n{}\n", block.trim_end_matches('\n')));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn stow_packages_are_one_per_tool() {
+        let file = |path: &str| ExportFile { section: "x".into(), path: path.into(), kind: ExportKind::File, content: String::new() };
+        let packages: Vec<String> = [
+            ".tmux.conf",
+            ".config/starship.toml",
+            ".config/ghostty/themes/tokyo-night",
+            "Library/Application Support/lazygit/config.yml",
+        ]
+        .iter()
+        .map(|path| stow_package(&file(path)))
+        .collect();
+        assert_eq!(packages, ["tmux", "starship", "ghostty", "lazygit"]);
+
+        let dir = export_dir("stow");
+        let config = GhosttyConfig::default();
+        export(&config, &dir, ExportFormat::Stow).unwrap();
+        let zsh = fs::read_to_string(dir.join("zsh").join(STOW_ZSH_FILE)).unwrap();
+        assert!(zsh.contains(ZSHRC_START_MARKER));
+        for file in rendered_files(&config).unwrap().iter().filter(|f| f.kind == ExportKind::File) {
+            assert!(dir.join(stow_package(file)).join(&file.path).is_file(), "{}", file.path);
+        }
+        let installer = dir.join(INSTALLER_FILE);
+        assert!(Command::new("bash").arg("-n").arg(&installer).status().unwrap().success());
+        let script = fs::read_to_string(&installer).unwrap();
+        assert!(script.contains(&format!("source_line='[[ -f ~/{0} ]] && source ~/{0}'", STOW_ZSH_FILE)), "{}", script);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -27,6 +27,93 @@ pub fn shell_escape(value: &Value, _args: &HashMap<String, Value>) -> TeraResult
     }
 }
 
+/// Escape a value for a TOML basic string ("..."). With `multiline=true`
+/// line breaks are kept, for use inside """...""".
+pub fn toml_escape(value: &Value, args: &HashMap<String, Value>) -> TeraResult<Value> {
+    let multiline = args.get("multiline").and_then(Value::as_bool).unwrap_or(false);
+    match value.as_str() {
+        Some(s) => {
+            let mut out = String::with_capacity(s.len());
+            for c in s.chars() {
+                match c {
+                    '\\' => out.push_str("\\\\"),
+                    '"' => out.push_str("\\\""),
+                    '\n' if multiline => out.push('\n'),
+                    '\n' => out.push_str("\\n"),
+                    '\t' => out.push('\t'),
+                    '\r' => out.push_str("\\r"),
+                    c if c.is_control() => out.push_str(&format!("\\u{:04X}", c as u32)),
+                    c => out.push(c),
+                }
+            }
+            Ok(Value::String(out))
+        }
+        _ => Ok(value.clone()),
+    }
+}
+
+/// Escape a value for a JSON string ("...")
+pub fn json_escape(value: &Value, _args: &HashMap<String, Value>) -> TeraResult<Value> {
+    match value.as_str() {
+        Some(s) => {
+            let quoted = serde_json::to_string(s).map_err(|e| tera::Error::msg(e.to_string()))?;
+            Ok(Value::String(quoted[1..quoted.len() - 1].to_string()))
+        }
+        _ => Ok(value.clone()),
+    }
+}
+
+/// Escape a value for a YAML double-quoted scalar. YAML's escapes are a
+/// superset of JSON's, so the JSON form is always valid.
+pub fn yaml_escape(value: &Value, args: &HashMap<String, Value>) -> TeraResult<Value> {
+    json_escape(value, args)
+}
+
+/// Escape a value for a Lua string ("..." or '...')
+pub fn lua_escape(value: &Value, _args: &HashMap<String, Value>) -> TeraResult<Value> {
+    match value.as_str() {
+        Some(s) => {
+            let mut out = String::with_capacity(s.len());
+            for c in s.chars() {
+                match c {
+                    '\\' => out.push_str("\\\\"),
+                    '"' => out.push_str("\\\""),
+                    '\'' => out.push_str("\\'"),
+                    '\n' => out.push_str("\\n"),
+                    '\r' => out.push_str("\\r"),
+                    '\t' => out.push_str("\\t"),
+                    // Three digits so a following digit is not swallowed
+                    c if c.is_ascii_control() => out.push_str(&format!("\\{:03}", c as u32)),
+                    c => out.push(c),
+                }
+            }
+            Ok(Value::String(out))
+        }
+        _ => Ok(value.clone()),
+    }
+}
+
+/// Escape a value for a single-quoted tmux string. tmux joins adjacent
+/// quoted parts like the shell, so a quote becomes '"'"'. Line breaks
+/// would end the command and are collapsed.
+pub fn tmux_escape(value: &Value, _args: &HashMap<String, Value>) -> TeraResult<Value> {
+    match value.as_str() {
+        Some(s) => {
+            let line = s.lines().map(str::trim).collect::<Vec<_>>().join(" ");
+            Ok(Value::String(line.replace('\'', "'\"'\"'")))
+        }
+        _ => Ok(value.clone()),
+    }
+}
+
+/// Collapse line breaks so a value cannot spill onto extra config lines
+pub fn single_line(value: &Value, _args: &HashMap<String, Value>) -> TeraResult<Value> {
+    match value.as_str() {
+        Some(s) => Ok(Value::String(s.lines().map(str::trim).collect::<Vec<_>>().join(" "))),
+        _ => Ok(value.clone()),
+    }
+}
+
 /// Convert a hex color like #0b0e14 to the palette index format
 pub fn hex_strip(value: &Value, _args: &HashMap<String, Value>) -> TeraResult<Value> {
     match value.as_str() {
@@ -34,3 +121,168 @@ pub fn hex_strip(value: &Value, _args: &HashMap<String, Value>) -> TeraResult<Va
         _ => Ok(value.clone()),
     }
 }
+
+/// Render a value as a Nix expression: strings are double-quoted, objects
+/// become attribute sets and arrays lists. Nested lines are indented by
+/// `indent` spaces, so the result can be placed inside a larger expression.
+pub fn nix(value: &Value, args: &HashMap<String, Value>) -> TeraResult<Value> {
+    let indent = args.get("indent").and_then(Value::as_u64).unwrap_or(0) as usize;
+    Ok(Value::String(nix_expr(value, indent)))
+}
+
+fn nix_expr(value: &Value, indent: usize) -> String {
+    let pad = " ".repeat(indent + 2);
+    match value {
+        Value::Null => "null".into(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => match n.as_f64() {
+            // Nix reads `1` as an integer, so floats keep their point
+            Some(f) if n.is_f64() => format!("{:?}", f),
+            _ => n.to_string(),
+        },
+        Value::String(s) => nix_string(s),
+        Value::Array(items) if items.is_empty() => "[ ]".into(),
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(|v| format!("{}{}", pad, nix_list_item(v, indent + 2))).collect();
+            format!("[\n{}\n{}]", items.join("\n"), " ".repeat(indent))
+        }
+        Value::Object(map) if map.is_empty() => "{ }".into(),
+        Value::Object(map) => {
+            let attrs: Vec<String> = map
+                .iter()
+                .map(|(k, v)| format!("{}{} = {};", pad, nix_attr_name(k), nix_expr(v, indent + 2)))
+                .collect();
+            format!("{{\n{}\n{}}}", attrs.join("\n"), " ".repeat(indent))
+        }
+    }
+}
+
+/// List items are separated by whitespace, so anything but a literal needs parentheses.
+fn nix_list_item(value: &Value, indent: usize) -> String {
+    match value {
+        Value::Number(n) if n.as_f64().is_some_and(|f| f < 0.0) => format!("({})", nix_expr(value, indent)),
+        _ => nix_expr(value, indent),
+    }
+}
+
+const NIX_KEYWORDS: [&str; 10] = ["assert", "else", "if", "in", "inherit", "let", "or", "rec", "then", "with"];
+
+/// An attribute name, quoted unless it is a plain identifier.
+pub fn nix_attr_name(name: &str) -> String {
+    let mut chars = name.chars();
+    let identifier = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '\'' | '-'))
+        && !NIX_KEYWORDS.contains(&name);
+    if identifier { name.to_string() } else { nix_string(name) }
+}
+
+fn nix_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '$' if chars.peek() == Some(&'{') => out.push_str("\\$"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Render a file's contents as a Nix string literal: an indented string
+/// ('' ... '') starting on its own line. Nix strips the indentation shared by
+/// every line of those, so text where every line is indented is
+/// double-quoted instead. Trailing quotes would run into the closing ''
+/// and are interpolated.
+pub fn nix_text(value: &Value, _args: &HashMap<String, Value>) -> TeraResult<Value> {
+    let Some(s) = value.as_str() else { return Ok(value.clone()) };
+    let body = s.trim_end_matches('\'');
+    let quotes = &s[body.len()..];
+    let flush_left = s.lines().any(|l| !l.trim().is_empty() && !l.starts_with([' ', '\t']));
+    // `$${` is not an interpolation in Nix
+    if !flush_left || (!quotes.is_empty() && body.ends_with('$')) {
+        return Ok(Value::String(nix_string(s)));
+    }
+    let mut out = format!("''\n{}", body.replace("''", "'''").replace("${", "''${"));
+    if !quotes.is_empty() {
+        out.push_str(&format!("${{\"{}\"}}", quotes));
+    }
+    out.push_str("''");
+    Ok(Value::String(out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(filter: fn(&Value, &HashMap<String, Value>) -> TeraResult<Value>, input: &str) -> String {
+        filter(&Value::String(input.into()), &HashMap::new()).unwrap().as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn escaped_values_parse_back_unchanged() {
+        let input = "a \"quoted\" \\ path\nwith\ttabs and 'ticks' \u{1}";
+        let toml = format!("key = \"{}\"", apply(toml_escape, input));
+        assert_eq!(toml.parse::<toml::Table>().unwrap()["key"].as_str(), Some(input));
+        let json = format!("\"{}\"", apply(json_escape, input));
+        assert_eq!(serde_json::from_str::<String>(&json).unwrap(), input);
+        let yaml = format!("key: \"{}\"", apply(yaml_escape, input));
+        let docs = yaml_rust2::YamlLoader::load_from_str(&yaml).unwrap();
+        assert_eq!(docs[0]["key"].as_str(), Some(input));
+    }
+
+    #[test]
+    fn multiline_toml_keeps_line_breaks() {
+        let mut args = HashMap::new();
+        args.insert("multiline".to_string(), Value::Bool(true));
+        let out = toml_escape(&Value::String("a\n\"b\"".into()), &args).unwrap();
+        assert_eq!(out.as_str(), Some("a\n\\\"b\\\""));
+    }
+
+    #[test]
+    fn lua_escape_keeps_following_digits() {
+        assert_eq!(apply(lua_escape, "it's \"x\"\n"), "it\\'s \\\"x\\\"\\n");
+        assert_eq!(apply(lua_escape, "\u{1}2"), "\\0012");
+    }
+
+    #[test]
+    fn shell_and_tmux_quotes_stay_on_one_line() {
+        assert_eq!(apply(shell_escape, "it's"), "it'\\''s");
+        assert_eq!(apply(tmux_escape, "it's\n  two"), "it'\"'\"'s two");
+        assert_eq!(apply(single_line, "one\n  two\n"), "one two");
+    }
+
+    #[test]
+    fn non_strings_pass_through() {
+        assert_eq!(toml_escape(&Value::Bool(true), &HashMap::new()).unwrap(), Value::Bool(true));
+        assert_eq!(bool_to_on_off(&Value::Bool(false), &HashMap::new()).unwrap(), Value::String("off".into()));
+    }
+
+    #[test]
+    fn nix_expressions_quote_what_needs_it() {
+        let value = serde_json::json!({ "a b": [1, -2, 1.0, "x${y}"], "if": true, "ok-name'": null });
+        let mut args = HashMap::new();
+        args.insert("indent".to_string(), Value::from(2));
+        let out = nix(&value, &args).unwrap();
+        assert_eq!(
+            out.as_str().unwrap(),
+            "{\n    \"a b\" = [\n      1\n      (-2)\n      1.0\n      \"x\\${y}\"\n    ];\n    \"if\" = true;\n    ok-name' = null;\n  }"
+        );
+    }
+
+    #[test]
+    fn nix_text_escapes_indented_string_syntax() {
+        assert_eq!(apply(nix_text, "a ''b'' ${c} $d\n"), "''\na '''b''' ''${c} $d\n''");
+        // Trailing quotes would close the string early
+        assert_eq!(apply(nix_text, "a''"), "''\na${\"''\"}''");
+        assert_eq!(apply(nix_text, "a$'"), "\"a$'\"");
+        // Nix would strip the indentation shared by every line
+        assert_eq!(apply(nix_text, "  x\n  y\n"), "\"  x\\n  y\\n\"");
+    }
+}
@@ -0,0 +1,266 @@
+use crate::config::schema::GhosttyConfig;
+use crate::config::sections::GitApplyMode;
+use super::applier::MANAGED_GITCONFIG;
+use super::engine;
+use super::export::{self, ExportFile, GHOSTTY_LOCAL};
+use super::filters::nix_attr_name;
+use super::registry;
+use serde_json::{json, Map, Value};
+
+/// File name of the module in a home-manager export.
+pub const MODULE_FILE: &str = "ghostty-ultimate.nix";
+
+/// Homebrew names whose nixpkgs attribute differs.
+const NIX_PACKAGES: [(&str, &str); 5] = [
+    ("git-delta", "delta"),
+    ("sevenzip", "_7zz"),
+    ("poppler", "poppler_utils"),
+    ("font-jetbrains-mono", "jetbrains-mono"),
+    ("font-jetbrains-mono-nerd-font", "nerd-fonts.jetbrains-mono"),
+];
+
+/// Plugins the zshrc block sources from `~/.local/share/<name>`, as the
+/// nixpkgs attribute and the directory under its `share/`.
+const ZSH_PLUGIN_PACKAGES: [(&str, &str, &str); 4] = [
+    ("zsh-autosuggestions", "zsh-autosuggestions", "zsh-autosuggestions"),
+    ("zsh-syntax-highlighting", "zsh-syntax-highlighting", "zsh-syntax-highlighting"),
+    ("fzf-tab", "zsh-fzf-tab", "fzf-tab"),
+    ("zsh-vi-mode", "zsh-vi-mode", "zsh-vi-mode"),
+];
+
+const BAT_THEME_FILE: &str = ".config/bat/themes/Ayu Dark.tmTheme";
+
+/// Remove the rendered file at `path` and return its content.
+fn take(files: &mut Vec<ExportFile>, path: &str) -> Option<String> {
+    let index = files.iter().position(|f| f.path == path)?;
+    Some(files.remove(index).content)
+}
+
+/// A rendered TOML file as a value the `nix` filter can print.
+fn toml_settings(path: &str, text: &str) -> Result<Value, String> {
+    let table: toml::Table = text.parse().map_err(|e| format!("{} is not valid TOML: {}", path, e))?;
+    serde_json::to_value(table).map_err(|e| e.to_string())
+}
+
+/// Git settings nested the way `programs.git.settings` expects:
+/// `section.subsection.name` becomes `{ section.subsection.name = value; }`,
+/// and a key set more than once becomes a list.
+fn git_settings(settings: &[(String, String)]) -> Value {
+    let mut root = Map::new();
+    for (key, value) in settings {
+        let Some((head, name)) = key.rsplit_once('.') else { continue };
+        let path: Vec<&str> = match head.split_once('.') {
+            Some((section, subsection)) => vec![section, subsection],
+            None => vec![head],
+        };
+        let mut node = &mut root;
+        for part in path {
+            let entry = node.entry(part.to_string()).or_insert_with(|| Value::Object(Map::new()));
+            if !entry.is_object() {
+                *entry = Value::Object(Map::new());
+            }
+            let Value::Object(next) = entry else { unreachable!() };
+            node = next;
+        }
+        match node.get_mut(name) {
+            Some(Value::Array(values)) => values.push(json!(value)),
+            Some(previous) => *previous = json!([previous.clone(), value]),
+            None => {
+                node.insert(name.to_string(), json!(value));
+            }
+        }
+    }
+    Value::Object(root)
+}
+
+/// `programs.git`: the managed settings, plus one conditional include per
+/// identity directory in place of the `includeIf` keys and include files.
+fn git_program(config: &GhosttyConfig, files: &mut Vec<ExportFile>) -> Option<Value> {
+    let git = &config.git;
+    if git.apply_mode == GitApplyMode::Remove || take(files, MANAGED_GITCONFIG).is_none() {
+        return None;
+    }
+    let settings: Vec<(String, String)> = git.settings(&config.palette).into_iter().chain(git.signing.settings()).collect();
+    let mut includes = Vec::new();
+    for identity in git.identities.iter().filter(|i| i.enabled && i.has_valid_id()) {
+        take(files, &identity.include_file());
+        for pattern in identity.gitdir_patterns() {
+            includes.push(json!({
+                "condition": format!("gitdir:{}", pattern),
+                "contents": git_settings(&identity.settings()),
+            }));
+        }
+    }
+    Some(json!({ "settings": git_settings(&settings), "includes": includes }))
+}
+
+/// Everything the module template needs. Tools home-manager has a module
+/// for are configured through it; the rest of the rendered files become
+/// `xdg.configFile` or `home.file` entries.
+pub fn module_vars(config: &GhosttyConfig) -> Result<Value, String> {
+    let mut files = export::rendered_files(config)?;
+    // The overrides file is the user's to edit, and home-manager links read-only files
+    take(&mut files, GHOSTTY_LOCAL);
+
+    let brew = export::brew_packages(config);
+    let installs = |name: &str| brew.iter().any(|(n, _)| n == name);
+
+    let starship = match take(&mut files, ".config/starship.toml") {
+        Some(text) => Some(toml_settings(".config/starship.toml", &text)?),
+        None => None,
+    };
+    let atuin = match take(&mut files, ".config/atuin/config.toml") {
+        Some(text) => Some(toml_settings(".config/atuin/config.toml", &text)?),
+        None => None,
+    };
+    let tmux = take(&mut files, ".tmux.conf");
+    let zsh = take(&mut files, ".zshrc");
+    let git = git_program(config, &mut files);
+    let bat = installs("bat").then(|| {
+        json!({ "theme": config.zshrc.bat_theme, "theme_file": take(&mut files, BAT_THEME_FILE) })
+    });
+    let fzf = installs("fzf");
+
+    // Packages a program module already installs are left out of home.packages
+    let mut provided = vec!["bat", "fzf"];
+    if starship.is_some() {
+        provided.push("starship");
+    }
+    if atuin.is_some() {
+        provided.push("atuin");
+    }
+    if tmux.is_some() {
+        provided.push("tmux");
+    }
+    let mut packages = Vec::new();
+    let mut unmapped = Vec::new();
+    for (name, _) in brew.iter().filter(|(name, _)| !provided.contains(&name.as_str())) {
+        let attr = NIX_PACKAGES.iter().find(|(brew, _)| brew == name).map_or(name.as_str(), |(_, attr)| attr);
+        if attr.split('.').all(|part| nix_attr_name(part) == part) {
+            packages.push(attr.to_string());
+        } else {
+            unmapped.push(name.clone());
+        }
+    }
+    let fonts = brew.iter().any(|(name, cask)| *cask && name.starts_with("font-"));
+
+    let mut plugins = Vec::new();
+    if registry::is_enabled(config, "zsh_plugins") {
+        for plugin in config.zsh_plugins.plugins.iter().filter(|p| p.enabled) {
+            match ZSH_PLUGIN_PACKAGES.iter().find(|(name, _, _)| *name == plugin.name) {
+                Some((name, attr, dir)) => plugins.push(json!({ "name": name, "package": attr, "dir": dir })),
+                None => unmapped.push(format!("{} ({})", plugin.name, plugin.repo)),
+            }
+        }
+    }
+
+    let (config_files, home_files): (Vec<ExportFile>, Vec<ExportFile>) =
+        files.into_iter().partition(|f| f.path.starts_with(".config/"));
+    let entries = |files: Vec<ExportFile>, strip: &str| -> Vec<Value> {
+        files
+            .into_iter()
+            .map(|f| json!({ "path": f.path.strip_prefix(strip).unwrap_or(&f.path), "text": f.content }))
+            .collect()
+    };
+
+    Ok(json!({
+        "packages": packages,
+        "unmapped": unmapped,
+        "fonts": fonts,
+        "starship": starship,
+        "atuin": atuin,
+        "tmux": tmux,
+        "zsh": zsh,
+        "git": git,
+        "bat": bat,
+        "fzf": fzf,
+        "zsh_plugins": plugins,
+        "config_files": entries(config_files, ".config/"),
+        "home_files": entries(home_files, ""),
+    }))
+}
+
+/// Render the home-manager module for the config.
+pub fn generate(config: &GhosttyConfig) -> Result<String, String> {
+    engine::render_export_script(config, "home_manager.nix.tera", &module_vars(config)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(settings: &[(&str, &str)]) -> Vec<(String, String)> {
+        settings.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn git_settings_nest_by_section_and_subsection() {
+        let settings = git_settings(&pairs(&[
+            ("core.pager", "delta"),
+            ("delta.side-by-side", "true"),
+            ("url.git@github.com:.insteadOf", "https://github.com/"),
+        ]));
+        assert_eq!(
+            settings,
+            json!({
+                "core": { "pager": "delta" },
+                "delta": { "side-by-side": "true" },
+                "url": { "git@github.com:": { "insteadOf": "https://github.com/" } },
+            })
+        );
+    }
+
+    #[test]
+    fn repeated_git_keys_become_a_list() {
+        let settings = git_settings(&pairs(&[("a.b", "1"), ("a.b", "2"), ("a.b", "3"), ("nodot", "x")]));
+        assert_eq!(settings, json!({ "a": { "b": ["1", "2", "3"] } }));
+    }
+
+    #[test]
+    fn module_renders_with_the_default_config() {
+        let module = generate(&GhosttyConfig::default()).unwrap();
+        assert!(module.starts_with("# ── Ghostty Ultimate — home-manager module"));
+        assert!(module.contains("programs.git = {"));
+        assert!(!module.contains("includeIf"));
+    }
+
+    #[test]
+    fn native_modules_take_their_files_and_the_rest_are_linked() {
+        let mut config = GhosttyConfig::default();
+        config.ghostty.layout = crate::config::sections::GhosttyLayout::Modular;
+        config.git.identities = vec![crate::config::sections::GitIdentity {
+            id: "work".into(),
+            enabled: true,
+            user_name: "Work Name".into(),
+            email: "me@work.example".into(),
+            signing_key: String::new(),
+            directories: vec!["~/work".into()],
+        }];
+        config.zsh_plugins.plugins.push(crate::config::sections::ZshPlugin {
+            name: "zsh-you-should-use".into(),
+            repo: "MichaelAquilina/zsh-you-should-use".into(),
+            enabled: true,
+        });
+        let vars = module_vars(&config).unwrap();
+
+        let paths = |key: &str| -> Vec<String> {
+            vars[key].as_array().unwrap().iter().map(|f| f["path"].as_str().unwrap().to_string()).collect()
+        };
+        let config_files = paths("config_files");
+        let home_files = paths("home_files");
+        assert!(config_files.contains(&"ghostty/config".to_string()), "{:?}", config_files);
+        assert!(!config_files.iter().any(|p| p == "ghostty/local" || p == "starship.toml" || p.starts_with("git/")));
+        assert!(!home_files.iter().any(|p| p == ".zshrc" || p == ".tmux.conf"), "{:?}", home_files);
+        assert!(vars["starship"].is_object() && vars["tmux"].is_string() && vars["zsh"].is_string());
+
+        let packages: Vec<&str> = vars["packages"].as_array().unwrap().iter().filter_map(Value::as_str).collect();
+        assert!(packages.contains(&"delta") && packages.contains(&"_7zz"), "{:?}", packages);
+        assert!(!packages.contains(&"starship") && !packages.contains(&"bat"), "{:?}", packages);
+        assert!(vars["unmapped"].as_array().unwrap().contains(&json!("zsh-you-should-use (MichaelAquilina/zsh-you-should-use)")));
+
+        assert_eq!(
+            vars["git"]["includes"],
+            json!([{ "condition": "gitdir:~/work/", "contents": { "user": { "name": "Work Name", "email": "me@work.example" } } }])
+        );
+    }
+}
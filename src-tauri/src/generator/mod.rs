@@ -1,3 +1,13 @@
 pub mod engine;
 pub mod filters;
+pub mod overrides;
+pub mod custom;
+pub mod registry;
 pub mod applier;
+pub mod selection;
+pub mod transaction;
+pub mod signing;
+pub mod shaders;
+pub mod uninstall;
+pub mod export;
+pub mod home_manager;
@@ -0,0 +1,310 @@
+use crate::config::schema::GhosttyConfig;
+use super::engine;
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tera::ast::{Block, Node, WS};
+use tera::{Template, Tera};
+
+/// User template overrides, relative to the home directory. A file here
+/// replaces the built-in template with the same relative name.
+pub const OVERRIDE_DIR: &str = ".config/ghostty-ultimate-ui/templates";
+
+/// Built-ins stay reachable under this prefix, so an override can
+/// `{% extends "builtin/apply/tmux_config.tera" %}` and replace only the
+/// blocks it cares about.
+pub const BUILTIN_PREFIX: &str = "builtin/";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateOverride {
+    /// Template name, e.g. `apply/tmux_config.tera`.
+    pub name: String,
+    pub path: String,
+    /// Whether a built-in template with this name exists; other files are
+    /// only reachable through `{% include %}` or `{% import %}`.
+    pub replaces_builtin: bool,
+    /// Unified diff from the built-in source to the override.
+    pub diff: String,
+    /// Unified diff of the rendered output with the current config; empty
+    /// for partials and overrides that fail.
+    pub output_diff: String,
+    /// Compile or render error; a template that fails to compile is
+    /// ignored and the built-in is used instead.
+    pub error: Option<String>,
+}
+
+pub fn override_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(OVERRIDE_DIR))
+}
+
+fn collect_files(root: &Path, dir: &Path, out: &mut Vec<(String, PathBuf)>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(root, &path, out);
+        } else if path.extension().is_some_and(|e| e == "tera") {
+            let name = path.strip_prefix(root).unwrap_or(&path);
+            let name = name.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+            out.push((name, path));
+        }
+    }
+}
+
+/// Every `.tera` file in the override directory, by template name.
+pub fn override_files() -> Vec<(String, PathBuf)> {
+    let mut files = Vec::new();
+    if let Some(dir) = override_dir() {
+        collect_files(&dir, &dir, &mut files);
+    }
+    files.sort();
+    files
+}
+
+// ── Inheritance ─────────────────────────────────────────────────────
+
+/// Tera cannot `{% include %}` a template that extends another, and the
+/// scripts include the apply templates. Rewrite each extending template in
+/// `names` into a standalone one: the root parent's AST with every block
+/// resolved to its most specific definition.
+pub(crate) fn flatten_inheritance(tera: &mut Tera, names: &[String]) -> tera::Result<()> {
+    for name in names {
+        let Some(template) = tera.templates.get(name) else { continue };
+        if template.parents.is_empty() {
+            continue;
+        }
+        let mut chain = vec![template];
+        for parent in &template.parents {
+            chain.push(tera.get_template(parent)?);
+        }
+        let definitions: Vec<_> = chain.iter().map(|t| block_definitions(&t.ast)).collect();
+        let root = chain[chain.len() - 1];
+        let ast = resolve(&root.ast, &definitions, None)?;
+
+        let mut flat = Template::new(name, template.path.clone(), "")?;
+        flat.ast = ast;
+        for t in &chain {
+            flat.imported_macro_files.extend(t.imported_macro_files.iter().cloned());
+            for (macro_name, definition) in &t.macros {
+                flat.macros.entry(macro_name.clone()).or_insert_with(|| definition.clone());
+            }
+        }
+        tera.templates.insert(name.clone(), flat);
+    }
+    tera.build_inheritance_chains()
+}
+
+/// Every block a template defines. Tera's own `blocks` map leaves out
+/// blocks nested in an `if` or filter section.
+fn block_definitions(nodes: &[Node]) -> HashMap<&str, &Block> {
+    fn walk<'a>(nodes: &'a [Node], out: &mut HashMap<&'a str, &'a Block>) {
+        for node in nodes {
+            match node {
+                Node::Block(_, block, _) => {
+                    out.entry(block.name.as_str()).or_insert(block);
+                    walk(&block.body, out);
+                }
+                Node::If(cond, _) => {
+                    for (_, _, body) in &cond.conditions {
+                        walk(body, out);
+                    }
+                    if let Some((_, body)) = &cond.otherwise {
+                        walk(body, out);
+                    }
+                }
+                Node::Forloop(_, forloop, _) => walk(&forloop.body, out),
+                Node::FilterSection(_, section, _) => walk(&section.body, out),
+                _ => {}
+            }
+        }
+    }
+    let mut out = HashMap::new();
+    walk(nodes, &mut out);
+    out
+}
+
+/// `definitions` holds each template's blocks, child first. `current` is the
+/// block being resolved and the index of the definition in use, which is
+/// what a `super()` refers past.
+fn resolve(
+    nodes: &[Node],
+    definitions: &[HashMap<&str, &Block>],
+    current: Option<(&str, usize)>,
+) -> tera::Result<Vec<Node>> {
+    let mut out = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        out.push(match node {
+            Node::Block(ws, block, end_ws) => {
+                let (level, definition) = definitions
+                    .iter()
+                    .enumerate()
+                    .find_map(|(i, blocks)| blocks.get(block.name.as_str()).map(|b| (i, *b)))
+                    .unwrap_or((definitions.len() - 1, block));
+                let body = resolve(&definition.body, definitions, Some((&block.name, level)))?;
+                Node::Block(*ws, Block { name: block.name.clone(), body }, *end_ws)
+            }
+            Node::Super => {
+                let (name, level) = current.ok_or_else(|| tera::Error::msg("super() used outside a block"))?;
+                let (parent_level, definition) = definitions
+                    .iter()
+                    .enumerate()
+                    .skip(level + 1)
+                    .find_map(|(i, blocks)| blocks.get(name).map(|b| (i, *b)))
+                    .ok_or_else(|| tera::Error::msg(format!("super() in block '{}' has no parent definition", name)))?;
+                let body = resolve(&definition.body, definitions, Some((name, parent_level)))?;
+                // A flat template has no block definitions, so this just renders its body
+                Node::Block(WS::default(), Block { name: format!("{}.super", name), body }, WS::default())
+            }
+            Node::If(cond, ws) => {
+                let mut cond = cond.clone();
+                for (_, _, body) in cond.conditions.iter_mut() {
+                    *body = resolve(body, definitions, current)?;
+                }
+                if let Some((_, body)) = cond.otherwise.as_mut() {
+                    *body = resolve(body, definitions, current)?;
+                }
+                Node::If(cond, *ws)
+            }
+            Node::Forloop(ws, forloop, end_ws) => {
+                let mut forloop = forloop.clone();
+                forloop.body = resolve(&forloop.body, definitions, current)?;
+                if let Some(body) = forloop.empty_body.as_mut() {
+                    *body = resolve(body, definitions, current)?;
+                }
+                Node::Forloop(*ws, forloop, *end_ws)
+            }
+            Node::FilterSection(ws, section, end_ws) => {
+                let mut section = section.clone();
+                section.body = resolve(&section.body, definitions, current)?;
+                Node::FilterSection(*ws, section, *end_ws)
+            }
+            // Only the root's extends-free AST is walked
+            other => other.clone(),
+        });
+    }
+    Ok(out)
+}
+
+// ── Listing ─────────────────────────────────────────────────────────
+
+/// List the active overrides with their diff against the built-in, and
+/// check that each one compiles and renders with `config`.
+pub fn list_overrides(config: &GhosttyConfig) -> Result<Vec<TemplateOverride>, String> {
+    let files = override_files();
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+    let compiled = engine::compiled()?;
+    let ctx = engine::build_context(config)?;
+    let builtins = engine::builtin_sources();
+
+    let mut result = Vec::new();
+    for (name, path) in files {
+        let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let builtin = builtins.iter().find(|(n, _)| *n == name).map(|(_, source)| source.as_str());
+        let builtin_name = match builtin {
+            Some(_) => format!("{}{}", BUILTIN_PREFIX, name),
+            None => "/dev/null".to_string(),
+        };
+        let diff = unified_diff(builtin.unwrap_or(""), &content, &builtin_name, &name);
+
+        let mut error = compiled.template_errors.iter().find(|(n, _)| *n == name).map(|(_, e)| e.clone());
+        let mut output_diff = String::new();
+        // Partials are rendered by the templates that include them
+        if builtin.is_some() && error.is_none() {
+            let render = |template: &str| compiled.tera.render(template, &ctx).map_err(|e| engine::error_chain(&e));
+            match (render(&builtin_name), render(&name)) {
+                (Ok(before), Ok(after)) => output_diff = unified_diff(&before, &after, &builtin_name, &name),
+                (_, Err(e)) | (Err(e), _) => error = Some(e),
+            }
+        }
+        result.push(TemplateOverride {
+            name,
+            path: path.to_string_lossy().to_string(),
+            replaces_builtin: builtin.is_some(),
+            diff,
+            output_diff,
+            error,
+        });
+    }
+    Ok(result)
+}
+
+fn unified_diff(old: &str, new: &str, old_name: &str, new_name: &str) -> String {
+    TextDiff::from_lines(old, new).unified_diff().header(old_name, new_name).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flatten `name` and render it through an `{% include %}`, the way the
+    /// section scripts use the apply templates.
+    fn render_included(templates: &[(&str, &str)], name: &str, ctx: &tera::Context) -> String {
+        let mut tera = Tera::default();
+        tera.add_raw_templates(templates.to_vec()).unwrap();
+        flatten_inheritance(&mut tera, &[name.to_string()]).unwrap();
+        tera.add_raw_template("script", &format!("{{% include \"{}\" %}}", name)).unwrap();
+        tera.render("script", ctx).unwrap()
+    }
+
+    #[test]
+    fn single_level_extends_replaces_only_its_blocks() {
+        let templates = [
+            ("base", "a{% block x %}base-x{% endblock %}b{% block y %}base-y{% endblock %}"),
+            ("over", r#"{% extends "base" %}{% block x %}over-x{% endblock %}"#),
+        ];
+        assert_eq!(render_included(&templates, "over", &tera::Context::new()), "aover-xbbase-y");
+    }
+
+    #[test]
+    fn super_reaches_each_parent_in_turn() {
+        let templates = [
+            ("base", "[{% block x %}1{% endblock %}]"),
+            ("mid", r#"{% extends "base" %}{% block x %}{{ super() }}2{% endblock %}"#),
+            ("leaf", r#"{% extends "mid" %}{% block x %}{{ super() }}3{% endblock %}"#),
+        ];
+        assert_eq!(render_included(&templates, "leaf", &tera::Context::new()), "[123]");
+        assert_eq!(render_included(&templates, "mid", &tera::Context::new()), "[12]");
+    }
+
+    /// Tera only allows blocks inside `if`, not `for`, but a block's body
+    /// can hold loops and an `if` around `super()`.
+    #[test]
+    fn blocks_inside_if_and_loops_inside_blocks_are_resolved() {
+        let templates = [
+            (
+                "base",
+                "{% if flag %}{% block on %}on{% endblock %}{% else %}{% block off %}off{% endblock %}{% endif %}\
+                 |{% block list %}{% for i in items %}{{ i }}{% endfor %}{% endblock %}",
+            ),
+            (
+                "over",
+                r#"{% extends "base" %}{% block on %}[{{ super() }}]{% endblock %}{% block list %}{% if items %}{{ super() }}!{% endif %}{% endblock %}"#,
+            ),
+        ];
+        let mut ctx = tera::Context::new();
+        ctx.insert("items", &[1, 2, 3]);
+        ctx.insert("flag", &true);
+        assert_eq!(render_included(&templates, "over", &ctx), "[on]|123!");
+        ctx.insert("flag", &false);
+        assert_eq!(render_included(&templates, "over", &ctx), "off|123!");
+    }
+
+    #[test]
+    fn super_outside_a_parent_block_is_an_error() {
+        let mut tera = Tera::default();
+        tera.add_raw_templates(vec![
+            ("base", "{% block x %}1{% endblock %}"),
+            ("over", r#"{% extends "base" %}{% block x %}{{ super() }}{% block y %}{{ super() }}{% endblock %}{% endblock %}"#),
+        ])
+        .unwrap();
+        let error = flatten_inheritance(&mut tera, &["over".to_string()]).unwrap_err();
+        assert!(error.to_string().contains("super() in block 'y' has no parent definition"), "{}", error);
+    }
+}
@@ -0,0 +1,287 @@
+use crate::config::requirements::Requirement;
+use crate::config::schema::GhosttyConfig;
+use super::applier::{self, ApplyTarget};
+use super::custom;
+use super::transaction::Journal;
+use serde::{Deserialize, Serialize};
+
+/// Writes a section's config files, recording every change in the journal.
+pub type ApplyFn = fn(&GhosttyConfig, &ApplyTarget, &mut Journal) -> Result<String, String>;
+
+/// A built-in section. The table below is the single source for the
+/// script order, progress numbering, live apply and reload hooks.
+pub struct SectionDef {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub icon: &'static str,
+    /// Script template, rendered inside the section's `should_run` block.
+    pub template: &'static str,
+    /// Applied after these when both are part of the same apply-all.
+    pub depends_on: &'static [&'static str],
+    /// Run after the section is applied to the real home.
+    pub reload_hooks: &'static [&'static str],
+    pub enabled: fn(&GhosttyConfig) -> bool,
+    pub set_enabled: fn(&mut GhosttyConfig, bool),
+    /// `None` for script-only sections.
+    pub apply: Option<ApplyFn>,
+}
+
+/// Built-in sections in script order.
+pub const SECTIONS: &[SectionDef] = &[
+    SectionDef {
+        id: "clean_artifacts",
+        label: "Clean Artifacts",
+        icon: "🧹",
+        template: "clean_artifacts.sh.tera",
+        depends_on: &[],
+        reload_hooks: &[],
+        enabled: |c| c.clean_artifacts.enabled,
+        set_enabled: |c, on| c.clean_artifacts.enabled = on,
+        apply: None,
+    },
+    SectionDef {
+        id: "packages",
+        label: "Packages",
+        icon: "📦",
+        template: "packages.sh.tera",
+        depends_on: &[],
+        reload_hooks: &[],
+        enabled: |c| c.packages.enabled,
+        set_enabled: |c, on| c.packages.enabled = on,
+        apply: None,
+    },
+    SectionDef {
+        id: "ghostty",
+        label: "Ghostty",
+        icon: "👻",
+        template: "ghostty.sh.tera",
+        depends_on: &[],
+        reload_hooks: &["ghostty"],
+        enabled: |c| c.ghostty.enabled,
+        set_enabled: |c, on| c.ghostty.enabled = on,
+        apply: Some(applier::apply_ghostty),
+    },
+    SectionDef {
+        id: "starship",
+        label: "Starship",
+        icon: "🚀",
+        template: "starship.sh.tera",
+        depends_on: &[],
+        reload_hooks: &[],
+        enabled: |c| c.starship.enabled,
+        set_enabled: |c, on| c.starship.enabled = on,
+        apply: Some(applier::apply_starship),
+    },
+    SectionDef {
+        id: "fastfetch",
+        label: "Fastfetch",
+        icon: "📊",
+        template: "fastfetch.sh.tera",
+        depends_on: &[],
+        reload_hooks: &[],
+        enabled: |c| c.fastfetch.enabled,
+        set_enabled: |c, on| c.fastfetch.enabled = on,
+        apply: Some(applier::apply_fastfetch),
+    },
+    SectionDef {
+        id: "zsh_plugins",
+        label: "Zsh Plugins",
+        icon: "🧩",
+        template: "zsh_plugins.sh.tera",
+        depends_on: &[],
+        reload_hooks: &[],
+        enabled: |c| c.zsh_plugins.enabled,
+        set_enabled: |c, on| c.zsh_plugins.enabled = on,
+        apply: Some(applier::apply_zsh_plugins),
+    },
+    SectionDef {
+        id: "tmux",
+        label: "tmux",
+        icon: "🖥️",
+        template: "tmux.sh.tera",
+        // tmux popups launch lazygit/yazi/btop with their configs in place
+        depends_on: &["tool_configs", "themes"],
+        reload_hooks: &["tmux"],
+        enabled: |c| c.tmux.enabled,
+        set_enabled: |c, on| c.tmux.enabled = on,
+        apply: Some(applier::apply_tmux),
+    },
+    SectionDef {
+        id: "neovim",
+        label: "Neovim",
+        icon: "✏️",
+        template: "neovim.sh.tera",
+        depends_on: &[],
+        reload_hooks: &["neovim"],
+        enabled: |c| c.neovim.enabled,
+        set_enabled: |c, on| c.neovim.enabled = on,
+        apply: Some(applier::apply_neovim),
+    },
+    SectionDef {
+        id: "tool_configs",
+        label: "Tool Configs",
+        icon: "⚙️",
+        template: "tool_configs.sh.tera",
+        // lazygit's pager is delta, configured by the git section
+        depends_on: &["git"],
+        reload_hooks: &[],
+        enabled: |c| c.tool_configs.enabled,
+        set_enabled: |c, on| c.tool_configs.enabled = on,
+        apply: Some(applier::apply_tool_configs),
+    },
+    SectionDef {
+        id: "git",
+        label: "Git",
+        icon: "🔀",
+        template: "git.sh.tera",
+        // delta's syntax-theme names the bat theme the themes section installs
+        depends_on: &["themes"],
+        reload_hooks: &[],
+        enabled: |c| c.git.enabled,
+        set_enabled: |c, on| c.git.enabled = on,
+        apply: Some(applier::apply_git),
+    },
+    SectionDef {
+        id: "themes",
+        label: "Themes",
+        icon: "🎨",
+        template: "themes.sh.tera",
+        depends_on: &[],
+        reload_hooks: &["bat", "yazi"],
+        enabled: |c| c.themes.enabled,
+        set_enabled: |c, on| c.themes.enabled = on,
+        apply: Some(applier::apply_themes),
+    },
+    SectionDef {
+        id: "tmux_scripts",
+        label: "tmux Scripts",
+        icon: "📜",
+        template: "tmux_scripts.sh.tera",
+        depends_on: &[],
+        reload_hooks: &[],
+        enabled: |c| c.tmux_scripts.enabled,
+        set_enabled: |c, on| c.tmux_scripts.enabled = on,
+        apply: None,
+    },
+    SectionDef {
+        id: "zshrc",
+        label: "Zshrc",
+        icon: "🏠",
+        template: "zshrc.sh.tera",
+        // .zshrc sources plugins and exports BAT_THEME / starship init
+        depends_on: &["zsh_plugins", "themes", "starship"],
+        reload_hooks: &[],
+        enabled: |c| c.zshrc.enabled,
+        set_enabled: |c, on| c.zshrc.enabled = on,
+        apply: Some(applier::apply_zshrc),
+    },
+];
+
+/// Script template for manifest-defined sections.
+pub const CUSTOM_TEMPLATE: &str = "custom_section.sh.tera";
+
+pub fn builtin(id: &str) -> Option<&'static SectionDef> {
+    SECTIONS.iter().find(|s| s.id == id)
+}
+
+/// A built-in or custom section, as listed by `list_sections`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionInfo {
+    pub id: String,
+    pub label: String,
+    pub icon: String,
+    /// 1-based position in the script.
+    pub number: usize,
+    pub template: String,
+    pub depends_on: Vec<String>,
+    pub reload_hooks: Vec<String>,
+    pub live_apply: bool,
+    /// Defined by a manifest in ~/.config/ghostty-ultimate-ui/sections.
+    pub custom: bool,
+}
+
+/// Every section in script order: the built-ins, then the custom sections.
+pub fn list_sections() -> Vec<SectionInfo> {
+    let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    let builtins = SECTIONS.iter().map(|s| SectionInfo {
+        id: s.id.to_string(),
+        label: s.label.to_string(),
+        icon: s.icon.to_string(),
+        number: 0,
+        template: s.template.to_string(),
+        depends_on: strings(s.depends_on),
+        reload_hooks: strings(s.reload_hooks),
+        live_apply: s.apply.is_some(),
+        custom: false,
+    });
+    let customs = custom::registry();
+    let customs: Vec<SectionInfo> = customs
+        .sections
+        .iter()
+        .map(|m| SectionInfo {
+            id: m.id.clone(),
+            label: m.label.clone(),
+            icon: m.icon.clone(),
+            number: 0,
+            template: CUSTOM_TEMPLATE.to_string(),
+            depends_on: custom_dependencies(m),
+            reload_hooks: m
+                .reload
+                .iter()
+                .map(|hook| match hook {
+                    custom::ReloadHook::Builtin(name) => name.clone(),
+                    custom::ReloadHook::Command { command } => command.join(" "),
+                })
+                .collect(),
+            live_apply: true,
+            custom: true,
+        })
+        .collect();
+    builtins
+        .chain(customs)
+        .enumerate()
+        .map(|(i, s)| SectionInfo { number: i + 1, ..s })
+        .collect()
+}
+
+/// Every section id in script order.
+pub fn section_ids() -> Vec<String> {
+    SECTIONS.iter().map(|s| s.id.to_string()).chain(custom::registry().ids().map(String::from)).collect()
+}
+
+/// The sections a custom section requires.
+fn custom_dependencies(manifest: &custom::SectionManifest) -> Vec<String> {
+    manifest
+        .requires
+        .iter()
+        .filter_map(|r| match r {
+            Requirement::Section(id) => Some(id.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The sections `id` is applied after, built-in or custom.
+pub fn dependencies(id: &str) -> Vec<String> {
+    match builtin(id) {
+        Some(def) => def.depends_on.iter().map(|d| d.to_string()).collect(),
+        None => custom::registry().get(id).map(custom_dependencies).unwrap_or_default(),
+    }
+}
+
+pub fn is_known(id: &str) -> bool {
+    builtin(id).is_some() || custom::registry().get(id).is_some()
+}
+
+/// Whether a section is switched on in the config.
+pub fn is_enabled(config: &GhosttyConfig, id: &str) -> bool {
+    match builtin(id) {
+        Some(section) => (section.enabled)(config),
+        None => custom::is_enabled(config, id),
+    }
+}
+
+/// The sections the generated script runs, in order.
+pub fn enabled_sections(config: &GhosttyConfig) -> Vec<SectionInfo> {
+    list_sections().into_iter().filter(|s| is_enabled(config, &s.id)).collect()
+}
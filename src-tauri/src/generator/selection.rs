@@ -0,0 +1,53 @@
+use super::registry;
+use serde::{Deserialize, Serialize};
+
+/// Which sections a generated script should run.
+///
+/// The same flags are accepted by the script itself (`--only`, `--skip`,
+/// `--from`, `--resume`); a selection passed to `generate` becomes the
+/// script's default when it is run without arguments.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SectionSelection {
+    #[serde(default)]
+    pub only: Vec<String>,
+    #[serde(default)]
+    pub skip: Vec<String>,
+    #[serde(default)]
+    pub from: Option<String>,
+    /// Skip sections recorded as completed in the script's state file.
+    #[serde(default)]
+    pub resume: bool,
+}
+
+impl SectionSelection {
+    pub fn validate(&self) -> Result<(), String> {
+        let ids = self.only.iter().chain(self.skip.iter()).chain(self.from.iter());
+        for id in ids {
+            if !registry::is_known(id) {
+                return Err(format!("Unknown section '{}'", id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Command-line flags understood by the generated script.
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if !self.only.is_empty() {
+            args.push("--only".to_string());
+            args.push(self.only.join(","));
+        }
+        if !self.skip.is_empty() {
+            args.push("--skip".to_string());
+            args.push(self.skip.join(","));
+        }
+        if let Some(from) = &self.from {
+            args.push("--from".to_string());
+            args.push(from.clone());
+        }
+        if self.resume {
+            args.push("--resume".to_string());
+        }
+        args
+    }
+}
@@ -0,0 +1,308 @@
+use crate::config::schema::GhosttyConfig;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Shader library, relative to the home directory.
+pub const SHADER_DIR: &str = ".config/ghostty/shaders";
+
+/// What Ghostty wraps a Shadertoy-style shader in before compiling it. The
+/// channel is split into texture + sampler because naga has no combined
+/// samplers; `iChannel0` still reads the same in user code.
+const PRELUDE: &str = "#version 450
+layout(set = 0, binding = 0) uniform Globals {
+  vec3 iResolution;
+  float iTime;
+  float iTimeDelta;
+  float iFrameRate;
+  int iFrame;
+  vec4 iMouse;
+  vec4 iDate;
+  vec4 iCurrentCursor;
+  vec4 iPreviousCursor;
+  vec4 iCurrentCursorColor;
+  vec4 iPreviousCursorColor;
+  float iTimeCursorChange;
+};
+layout(set = 0, binding = 1) uniform texture2D _iChannel0Texture;
+layout(set = 0, binding = 2) uniform sampler _iChannel0Sampler;
+#define iChannel0 sampler2D(_iChannel0Texture, _iChannel0Sampler)
+layout(location = 0) out vec4 _fragColor;
+";
+
+const EPILOGUE: &str = "\nvoid main() { mainImage(_fragColor, gl_FragCoord.xy); }\n";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShaderDiagnostic {
+    /// 1-based line in the shader file; `None` for whole-file problems.
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShaderInfo {
+    /// Path relative to the shader library, as used in `ghostty.shaders`.
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    pub valid: bool,
+    pub diagnostics: Vec<ShaderDiagnostic>,
+}
+
+/// Parse and validate a Ghostty shader on the CPU with naga, reporting
+/// errors against the shader's own line numbers.
+pub fn validate_glsl(source: &str) -> Vec<ShaderDiagnostic> {
+    if !source.contains("mainImage") {
+        return vec![ShaderDiagnostic {
+            line: None,
+            column: None,
+            message: "Shader does not define mainImage(out vec4, in vec2)".into(),
+        }];
+    }
+    if source.lines().any(|l| l.trim_start().starts_with("#version")) {
+        return vec![ShaderDiagnostic {
+            line: None,
+            column: None,
+            message: "Remove the #version line; Ghostty adds its own".into(),
+        }];
+    }
+
+    let full = format!("{}{}{}", PRELUDE, source, EPILOGUE);
+    let offset = PRELUDE.lines().count() as u32;
+    let source_lines = source.lines().count() as u32;
+    let diagnostic = |location: Option<naga::SourceLocation>, message: String| {
+        let line = location
+            .map(|l| l.line_number)
+            .filter(|&n| n > offset && n <= offset + source_lines)
+            .map(|n| n - offset);
+        ShaderDiagnostic { line, column: line.and(location.map(|l| l.line_position)), message }
+    };
+
+    let mut frontend = naga::front::glsl::Frontend::default();
+    let options = naga::front::glsl::Options::from(naga::ShaderStage::Fragment);
+    let module = match frontend.parse(&options, &full) {
+        Ok(module) => module,
+        Err(e) => {
+            return e
+                .errors
+                .iter()
+                .map(|err| diagnostic(Some(err.meta.location(&full)), err.kind.to_string()))
+                .collect();
+        }
+    };
+    let mut validator = naga::valid::Validator::new(naga::valid::ValidationFlags::all(), naga::valid::Capabilities::all());
+    match validator.validate(&module) {
+        Ok(_) => Vec::new(),
+        Err(e) => {
+            // The innermost span points at the offending expression
+            let location = e.spans().last().map(|(span, _)| span.location(&full));
+            let mut message = e.as_inner().to_string();
+            let mut source = std::error::Error::source(e.as_inner());
+            while let Some(cause) = source {
+                message = format!("{}: {}", message, cause);
+                source = cause.source();
+            }
+            vec![diagnostic(location, message)]
+        }
+    }
+}
+
+fn shader_info(dir: &Path, path: &Path) -> Result<ShaderInfo, String> {
+    let source = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let diagnostics = validate_glsl(&source);
+    Ok(ShaderInfo {
+        name: path.strip_prefix(dir).unwrap_or(path).to_string_lossy().to_string(),
+        path: path.to_string_lossy().to_string(),
+        size: source.len() as u64,
+        valid: diagnostics.is_empty(),
+        diagnostics,
+    })
+}
+
+fn collect_glsl(dir: &Path, out: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if path.is_dir() && !hidden {
+            collect_glsl(&path, out)?;
+        } else if path.extension().is_some_and(|e| e == "glsl") {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Every `.glsl` file in the library, including the community pack, with
+/// its validation result.
+pub fn list_shaders(home: &Path) -> Result<Vec<ShaderInfo>, String> {
+    let dir = home.join(SHADER_DIR);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut paths = Vec::new();
+    collect_glsl(&dir, &mut paths)?;
+    paths.sort();
+    paths.iter().map(|p| shader_info(&dir, p)).collect()
+}
+
+/// Copy a shader into the library after checking that it compiles.
+pub fn import_shader(home: &Path, source: &Path) -> Result<ShaderInfo, String> {
+    let file_name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Not a file: {}", source.display()))?;
+    if !file_name.ends_with(".glsl") {
+        return Err(format!("Shader files must end in .glsl: {}", file_name));
+    }
+    let content = fs::read_to_string(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+    if let Some(first) = validate_glsl(&content).first() {
+        return Err(match first.line {
+            Some(line) => format!("{} does not compile (line {}): {}", file_name, line, first.message),
+            None => format!("{} does not compile: {}", file_name, first.message),
+        });
+    }
+
+    let dir = home.join(SHADER_DIR);
+    let dest = dir.join(&file_name);
+    if dest.exists() {
+        return Err(format!("A shader named {} is already in the library", file_name));
+    }
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    fs::write(&dest, &content).map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+    shader_info(&dir, &dest)
+}
+
+/// Check every shader the config chains before it is written, so a broken
+/// or missing file never reaches Ghostty. The single `custom_shader_path`
+/// is only checked if present, since the install script creates it.
+pub fn validate_chain(config: &GhosttyConfig, home: &Path) -> Result<(), String> {
+    let dir = home.join(SHADER_DIR);
+    let check = |path: &Path, label: &str| match fs::read_to_string(path) {
+        Ok(source) => validate_glsl(&source).first().map(|first| {
+            let at = first.line.map(|l| format!(" (line {})", l)).unwrap_or_default();
+            format!("Shader {}{}: {}", label, at, first.message)
+        }),
+        Err(e) => Some(format!("Shader {}: {}", label, e)),
+    };
+
+    let mut errors = Vec::new();
+    let ghostty = &config.ghostty;
+    if ghostty.custom_shader_enabled {
+        let path = match ghostty.custom_shader_path.strip_prefix("~/") {
+            Some(rest) => home.join(rest),
+            None => PathBuf::from(&ghostty.custom_shader_path),
+        };
+        if path.exists() {
+            errors.extend(check(&path, &ghostty.custom_shader_path));
+        }
+    }
+    for shader in ghostty.shaders.iter().filter(|s| s.enabled) {
+        if !shader.has_valid_name() {
+            errors.push(format!("Shader name '{}' must be a relative .glsl path", shader.name));
+            continue;
+        }
+        errors.extend(check(&dir.join(&shader.name), &shader.name));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::sections::ShaderEntry;
+
+    const VALID: &str = "void mainImage(out vec4 fragColor, in vec2 fragCoord) {
+    vec2 uv = fragCoord / iResolution.xy;
+    fragColor = texture(iChannel0, uv);
+}
+";
+
+    fn home(test: &str) -> PathBuf {
+        let home = std::env::temp_dir().join(format!("ghostty-ultimate-shaders-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        fs::create_dir_all(&home).unwrap();
+        home
+    }
+
+    #[test]
+    fn shaders_are_validated_against_their_own_lines() {
+        assert!(validate_glsl(VALID).is_empty());
+
+        let missing = validate_glsl("void main() {}\n");
+        assert_eq!(missing[0].line, None);
+        assert!(missing[0].message.contains("mainImage"));
+        let version = validate_glsl(&format!("#version 330\n{}", VALID));
+        assert!(version[0].message.contains("#version"));
+
+        let broken = VALID.replace("fragColor = texture", "fragColor = nope");
+        let diagnostics = validate_glsl(&broken);
+        assert!(!diagnostics.is_empty());
+        assert_eq!(diagnostics[0].line, Some(3), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn imported_shaders_must_compile_and_be_new() {
+        let home = home("import");
+        let good = home.join("good.glsl");
+        let bad = home.join("bad.glsl");
+        fs::write(&good, VALID).unwrap();
+        fs::write(&bad, "void mainImage(out vec4 c, in vec2 p) { c = undefined; }\n").unwrap();
+
+        let info = import_shader(&home, &good).unwrap();
+        assert_eq!(info.name, "good.glsl");
+        assert!(info.valid);
+        assert!(import_shader(&home, &good).unwrap_err().contains("already in the library"));
+        assert!(import_shader(&home, &bad).unwrap_err().contains("does not compile (line 1)"));
+        fs::write(home.join("shader.frag"), VALID).unwrap();
+        assert!(import_shader(&home, &home.join("shader.frag")).unwrap_err().contains("must end in .glsl"));
+
+        let pack = home.join(SHADER_DIR).join("pack");
+        fs::create_dir_all(&pack).unwrap();
+        fs::write(pack.join("broken.glsl"), "void main() {}\n").unwrap();
+        fs::create_dir_all(home.join(SHADER_DIR).join(".git")).unwrap();
+        fs::write(home.join(SHADER_DIR).join(".git/hidden.glsl"), VALID).unwrap();
+        let shaders = list_shaders(&home).unwrap();
+        let names: Vec<&str> = shaders.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["good.glsl", "pack/broken.glsl"]);
+        assert!(!shaders[1].valid);
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn the_chain_is_checked_before_it_is_written() {
+        let home = home("chain");
+        let dir = home.join(SHADER_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("good.glsl"), VALID).unwrap();
+        fs::write(dir.join("bad.glsl"), "void mainImage(out vec4 c, in vec2 p) {\n  c = undefined;\n}\n").unwrap();
+
+        let mut config = GhosttyConfig::default();
+        // The install script writes the default shader, so a missing one is fine
+        config.ghostty.custom_shader_enabled = true;
+        let entry = |name: &str, enabled| ShaderEntry { name: name.into(), enabled };
+        config.ghostty.shaders = vec![entry("good.glsl", true), entry("bad.glsl", false)];
+        assert!(validate_chain(&config, &home).is_ok());
+
+        config.ghostty.shaders[1].enabled = true;
+        config.ghostty.shaders.push(entry("../escape.glsl", true));
+        config.ghostty.shaders.push(entry("missing.glsl", true));
+        let err = validate_chain(&config, &home).unwrap_err();
+        assert!(err.contains("Shader bad.glsl (line 2)"), "{}", err);
+        assert!(err.contains("'../escape.glsl' must be a relative .glsl path"), "{}", err);
+        assert!(err.contains("Shader missing.glsl: "), "{}", err);
+        assert!(!err.contains("good.glsl"), "{}", err);
+
+        fs::write(dir.join("vignette-bloom.glsl"), "broken").unwrap();
+        config.ghostty.shaders.clear();
+        config.ghostty.custom_shader_path = "~/.config/ghostty/shaders/vignette-bloom.glsl".into();
+        assert!(validate_chain(&config, &home).unwrap_err().contains("mainImage"));
+        fs::remove_dir_all(&home).unwrap();
+    }
+}
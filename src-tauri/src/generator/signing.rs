@@ -0,0 +1,243 @@
+use crate::config::schema::GhosttyConfig;
+use crate::config::sections::{GitSigningConfig, SigningFormat};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Result of trying the signing setup against a throwaway repository.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigningCheck {
+    pub key_found: bool,
+    /// A commit was created with `-S`.
+    pub signed: bool,
+    /// `git verify-commit` accepted it; `None` when it could not be checked
+    /// (OpenPGP, or SSH without an allowed-signers file).
+    pub verified: Option<bool>,
+    pub log: Vec<String>,
+}
+
+fn expand_home(path: &str, home: &Path) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Public key text for an SSH signing key: either a `key::` literal, the
+/// `.pub` file itself, or the `.pub` file next to a private key.
+pub fn ssh_public_key(key: &str, home: &Path) -> Result<String, String> {
+    if let Some(literal) = key.strip_prefix("key::") {
+        return Ok(literal.trim().to_string());
+    }
+    let path = expand_home(key, home);
+    let public = if path.extension().is_some_and(|e| e == "pub") {
+        path
+    } else {
+        PathBuf::from(format!("{}.pub", path.display()))
+    };
+    let content = fs::read_to_string(&public)
+        .map_err(|e| format!("Cannot read SSH public key {}: {}", public.display(), e))?;
+    // Drop the trailing comment: "<type> <base64> [comment]"
+    Ok(content.split_whitespace().take(2).collect::<Vec<_>>().join(" "))
+}
+
+/// Make sure the configured key is usable before git is pointed at it.
+pub fn validate_key(signing: &GitSigningConfig, home: &Path) -> Result<(), String> {
+    if signing.key.trim().is_empty() {
+        return Err("No signing key configured".into());
+    }
+    match signing.format {
+        SigningFormat::Ssh => {
+            if !signing.key.starts_with("key::") {
+                let path = expand_home(&signing.key, home);
+                if !path.exists() {
+                    return Err(format!("Signing key not found: {}", path.display()));
+                }
+            }
+            Ok(())
+        }
+        SigningFormat::Openpgp => {
+            let output = Command::new("gpg")
+                .args(["--list-secret-keys", &signing.key])
+                .output()
+                .map_err(|e| format!("Failed to run gpg: {}", e))?;
+            if !output.status.success() {
+                return Err(format!("No GPG secret key matches '{}'", signing.key));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Render the allowed-signers file: the default key under `default_email`,
+/// plus every identity that has its own SSH key.
+pub fn allowed_signers(config: &GhosttyConfig, home: &Path, default_email: Option<&str>) -> Result<String, String> {
+    let mut lines = Vec::new();
+    if let Some(email) = default_email.filter(|e| !e.is_empty()) {
+        lines.push(format!("{} namespaces=\"git\" {}", email, ssh_public_key(&config.git.signing.key, home)?));
+    }
+    for identity in config.git.identities.iter().filter(|i| i.enabled && !i.signing_key.is_empty()) {
+        lines.push(format!(
+            "{} namespaces=\"git\" {}",
+            identity.email,
+            ssh_public_key(&identity.signing_key, home)?
+        ));
+    }
+    Ok(lines.into_iter().map(|l| l + "\n").collect())
+}
+
+/// Sign an empty commit in a temporary repository using the configured
+/// settings (not the ones currently in the user's gitconfig), then verify it.
+pub fn check_signing(config: &GhosttyConfig, home: &Path) -> SigningCheck {
+    let signing = &config.git.signing;
+    let mut check = SigningCheck { key_found: false, signed: false, verified: None, log: vec![] };
+
+    if let Err(e) = validate_key(signing, home) {
+        check.log.push(e);
+        return check;
+    }
+    check.key_found = true;
+
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let repo = std::env::temp_dir().join(format!("ghostty-ultimate-signing-{}-{}", std::process::id(), nanos));
+    if let Err(e) = fs::create_dir_all(&repo) {
+        check.log.push(format!("Cannot create temp repo: {}", e));
+        return check;
+    }
+
+    let email = "signing-check@localhost";
+    let mut settings = signing.settings();
+    settings.retain(|(key, _)| key != "gpg.ssh.allowedSignersFile");
+    for (key, value) in settings.iter_mut() {
+        if key == "user.signingkey" || key == "gpg.ssh.program" {
+            *value = expand_home(value, home).to_string_lossy().to_string();
+        }
+    }
+    settings.push(("user.name".into(), "Signing Check".into()));
+    settings.push(("user.email".into(), email.into()));
+
+    if signing.format == SigningFormat::Ssh {
+        match ssh_public_key(&signing.key, home) {
+            Ok(key) => {
+                let signers = repo.join("allowed_signers");
+                if fs::write(&signers, format!("{} namespaces=\"git\" {}\n", email, key)).is_ok() {
+                    settings.push(("gpg.ssh.allowedSignersFile".into(), signers.to_string_lossy().to_string()));
+                }
+            }
+            Err(e) => check.log.push(e),
+        }
+    }
+
+    let git = |args: &[&str]| {
+        let mut cmd = Command::new("git");
+        cmd.current_dir(&repo);
+        for (key, value) in &settings {
+            cmd.arg("-c").arg(format!("{}={}", key, value));
+        }
+        cmd.args(args).output()
+    };
+
+    let steps: [(&str, &[&str]); 2] = [
+        ("git init", &["init", "-q"]),
+        ("git commit -S", &["commit", "-q", "--allow-empty", "-S", "-m", "signing check"]),
+    ];
+    for (label, args) in steps {
+        match git(args) {
+            Ok(out) if out.status.success() => check.log.push(format!("{}: ok", label)),
+            Ok(out) => {
+                check.log.push(format!("{} failed: {}", label, String::from_utf8_lossy(&out.stderr).trim()));
+                let _ = fs::remove_dir_all(&repo);
+                return check;
+            }
+            Err(e) => {
+                check.log.push(format!("{} failed: {}", label, e));
+                let _ = fs::remove_dir_all(&repo);
+                return check;
+            }
+        }
+    }
+    check.signed = true;
+
+    if settings.iter().any(|(key, _)| key == "gpg.ssh.allowedSignersFile") {
+        match git(&["verify-commit", "HEAD"]) {
+            Ok(out) => {
+                check.verified = Some(out.status.success());
+                let detail = String::from_utf8_lossy(&out.stderr).trim().to_string();
+                check.log.push(format!("git verify-commit: {}", if detail.is_empty() { "ok" } else { &detail }));
+            }
+            Err(e) => check.log.push(format!("git verify-commit failed: {}", e)),
+        }
+    }
+
+    let _ = fs::remove_dir_all(&repo);
+    check
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::sections::GitIdentity;
+
+    const PUBLIC_KEY: &str = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIExample";
+
+    /// A home with an SSH key pair; tests run in parallel, so each gets its own.
+    fn home(test: &str) -> PathBuf {
+        let home = std::env::temp_dir().join(format!("ghostty-ultimate-signing-{}-{}", test, std::process::id()));
+        fs::create_dir_all(home.join(".ssh")).unwrap();
+        fs::write(home.join(".ssh/id_ed25519"), "private").unwrap();
+        fs::write(home.join(".ssh/id_ed25519.pub"), format!("{} me@laptop\n", PUBLIC_KEY)).unwrap();
+        home
+    }
+
+    fn ssh(key: &str) -> GitSigningConfig {
+        GitSigningConfig { enabled: true, key: key.into(), ..Default::default() }
+    }
+
+    #[test]
+    fn ssh_keys_must_exist_unless_literal() {
+        let home = home("ssh");
+        assert!(validate_key(&ssh("~/.ssh/id_ed25519.pub"), &home).is_ok());
+        assert!(validate_key(&ssh(&format!("key::{}", PUBLIC_KEY)), &home).is_ok());
+        let missing = validate_key(&ssh("~/.ssh/missing"), &home).unwrap_err();
+        assert_eq!(missing, format!("Signing key not found: {}", home.join(".ssh/missing").display()));
+        assert_eq!(validate_key(&ssh("  "), &home).unwrap_err(), "No signing key configured");
+    }
+
+    #[test]
+    fn public_key_comes_from_the_pub_file_without_its_comment() {
+        let home = home("public");
+        assert_eq!(ssh_public_key("~/.ssh/id_ed25519", &home).unwrap(), PUBLIC_KEY);
+        assert_eq!(ssh_public_key("~/.ssh/id_ed25519.pub", &home).unwrap(), PUBLIC_KEY);
+        assert_eq!(ssh_public_key(&format!("key::{} ", PUBLIC_KEY), &home).unwrap(), PUBLIC_KEY);
+        assert!(ssh_public_key("~/.ssh/missing", &home).is_err());
+    }
+
+    #[test]
+    fn allowed_signers_list_the_default_key_and_identity_keys() {
+        let home = home("allowed");
+        let mut config = GhosttyConfig::default();
+        config.git.signing = ssh("~/.ssh/id_ed25519");
+        config.git.identities = vec![GitIdentity {
+            id: "work".into(),
+            enabled: true,
+            user_name: "Work Name".into(),
+            email: "me@work.example".into(),
+            signing_key: "key::ssh-ed25519 AAAAwork".into(),
+            directories: vec!["~/work".into()],
+        }];
+        let signers = allowed_signers(&config, &home, Some("me@home.example")).unwrap();
+        assert_eq!(
+            signers,
+            format!(
+                "me@home.example namespaces=\"git\" {}\nme@work.example namespaces=\"git\" ssh-ed25519 AAAAwork\n",
+                PUBLIC_KEY
+            )
+        );
+        // Without a global email only the identities are listed
+        assert_eq!(allowed_signers(&config, &home, Some("")).unwrap().lines().count(), 1);
+    }
+}
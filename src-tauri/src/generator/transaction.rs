@@ -0,0 +1,308 @@
+use crate::config::schema::GhosttyConfig;
+use super::applier::{apply_section_with, reload_hooks, section_enabled, ApplyTarget};
+use super::registry;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Record of everything an apply touched, so it can be undone.
+#[derive(Debug, Default)]
+pub struct Journal {
+    /// Files in first-touch order with their content before the apply (`None` = did not exist).
+    files: Vec<(PathBuf, Option<Vec<u8>>)>,
+    /// Directories created by the apply, outermost first.
+    dirs: Vec<PathBuf>,
+}
+
+impl Journal {
+    /// Snapshot a file before it is changed by something other than `write`
+    /// (e.g. `git config`). Only the first snapshot of a path is kept.
+    pub fn track(&mut self, path: &Path) {
+        if self.files.iter().any(|(p, _)| p == path) {
+            return;
+        }
+        self.files.push((path.to_path_buf(), fs::read(path).ok()));
+    }
+
+    pub fn write(&mut self, path: &Path, content: &str) -> Result<(), String> {
+        self.write_bytes(path, content.as_bytes())
+    }
+
+    pub fn write_bytes(&mut self, path: &Path, content: &[u8]) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            self.create_dirs(parent)?;
+        }
+        self.track(path);
+        fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Delete a file, keeping its content for rollback. Missing files are fine.
+    pub fn remove(&mut self, path: &Path) -> Result<(), String> {
+        self.track(path);
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Failed to remove {}: {}", path.display(), e))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn create_dirs(&mut self, dir: &Path) -> Result<(), String> {
+        let missing: Vec<PathBuf> = dir
+            .ancestors()
+            .take_while(|p| !p.as_os_str().is_empty() && !p.exists())
+            .map(|p| p.to_path_buf())
+            .collect();
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create directory {}: {}", dir.display(), e))?;
+        self.dirs.extend(missing.into_iter().rev());
+        Ok(())
+    }
+
+    pub fn paths(&self) -> Vec<String> {
+        self.files.iter().map(|(p, _)| p.display().to_string()).collect()
+    }
+
+    /// Restore every touched file to its recorded state, newest first.
+    /// Returns the paths that were restored or removed.
+    pub fn rollback(&mut self) -> Vec<String> {
+        let mut restored = Vec::new();
+        for (path, previous) in self.files.drain(..).rev() {
+            let result = match &previous {
+                Some(content) => fs::write(&path, content),
+                None if path.exists() => fs::remove_file(&path),
+                None => continue,
+            };
+            if result.is_ok() {
+                restored.push(path.display().to_string());
+            }
+        }
+        for dir in self.dirs.drain(..).rev() {
+            // Only removes directories left empty
+            let _ = fs::remove_dir(&dir);
+        }
+        restored
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Written,
+    Skipped,
+    Failed,
+    /// Written, then undone because a later step failed.
+    RolledBack,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepResult {
+    pub section: String,
+    pub status: StepStatus,
+    pub message: String,
+    pub files: Vec<String>,
+    /// Reload hook summaries, filled in once every step was written.
+    pub hook_output: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyReport {
+    pub success: bool,
+    pub steps: Vec<StepResult>,
+    /// Files restored (or removed) by the rollback.
+    pub rolled_back: Vec<String>,
+}
+
+/// Order the chosen sections so dependencies come first, otherwise keeping
+/// script order. Custom sections come after the built-in ones unless a
+/// built-in one requires them.
+pub fn apply_order(sections: &[String]) -> Result<Vec<String>, String> {
+    for section in sections {
+        if !registry::is_known(section) {
+            return Err(format!("Unknown section '{}'", section));
+        }
+    }
+
+    let ids = registry::section_ids();
+    let chosen: Vec<&str> = ids
+        .iter()
+        .map(String::as_str)
+        .filter(|id| sections.iter().any(|s| s == id))
+        .collect();
+    order_by_dependencies(&chosen, registry::dependencies)
+}
+
+/// Order `chosen` so every section follows the chosen ones it depends on,
+/// otherwise keeping the given order.
+fn order_by_dependencies(chosen: &[&str], dependencies: impl Fn(&str) -> Vec<String>) -> Result<Vec<String>, String> {
+    let deps: Vec<Vec<String>> = chosen
+        .iter()
+        .map(|id| dependencies(id).into_iter().filter(|dep| chosen.contains(&dep.as_str())).collect())
+        .collect();
+
+    let mut ordered: Vec<String> = Vec::new();
+    while ordered.len() < chosen.len() {
+        let next = chosen.iter().zip(&deps).find(|(id, deps)| {
+            !ordered.iter().any(|o| o == *id) && deps.iter().all(|dep| ordered.contains(dep))
+        });
+        match next {
+            Some((id, _)) => ordered.push(id.to_string()),
+            None => return Err("Section dependencies form a cycle".into()),
+        }
+    }
+    Ok(ordered)
+}
+
+/// Apply several sections as one transaction: if any step fails, every file
+/// written by earlier steps is restored.
+pub fn apply_all(config: &GhosttyConfig, sections: &[String], target: &ApplyTarget) -> Result<ApplyReport, String> {
+    let order = apply_order(sections)?;
+    let mut journal = Journal::default();
+    let mut steps: Vec<StepResult> = Vec::new();
+    let mut failed = false;
+
+    for section in order {
+        if failed {
+            steps.push(StepResult {
+                section,
+                status: StepStatus::Skipped,
+                message: "Not applied (earlier step failed)".into(),
+                files: vec![],
+                hook_output: vec![],
+            });
+            continue;
+        }
+        if !section_enabled(config, &section) {
+            steps.push(StepResult {
+                section,
+                status: StepStatus::Skipped,
+                message: "Section disabled".into(),
+                files: vec![],
+                hook_output: vec![],
+            });
+            continue;
+        }
+
+        let before = journal.paths();
+        let result = apply_section_with(config, &section, target, &mut journal);
+        let files = journal.paths().into_iter().filter(|p| !before.contains(p)).collect();
+        let (status, message) = match result {
+            Ok(message) => (StepStatus::Written, message),
+            Err(e) => {
+                failed = true;
+                (StepStatus::Failed, e)
+            }
+        };
+        steps.push(StepResult { section, status, message, files, hook_output: vec![] });
+    }
+
+    let rolled_back = if failed {
+        for step in steps.iter_mut().filter(|s| s.status == StepStatus::Written) {
+            step.status = StepStatus::RolledBack;
+        }
+        journal.rollback()
+    } else {
+        // Reload hooks only run once every step has been written. The files
+        // are in place either way, so a hook error is reported on its step.
+        for step in steps.iter_mut().filter(|s| s.status == StepStatus::Written) {
+            step.hook_output = reload_hooks(&step.section, target)
+                .unwrap_or_else(|e| vec![format!("Reload hooks failed: {}", e)]);
+        }
+        Vec::new()
+    };
+
+    Ok(ApplyReport { success: !failed, steps, rolled_back })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(sections: &[&str]) -> Result<Vec<String>, String> {
+        apply_order(&sections.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn dependencies_are_applied_first() {
+        assert_eq!(order(&["zshrc", "git", "themes", "tmux"]).unwrap(), vec!["themes", "tmux", "git", "zshrc"]);
+        // Without their dependencies in the selection, sections keep script order
+        assert_eq!(order(&["git", "neovim"]).unwrap(), vec!["neovim", "git"]);
+        assert!(order(&["git", "nope"]).is_err());
+    }
+
+    #[test]
+    fn custom_section_dependencies_are_followed() {
+        let dependencies = |id: &str| match id {
+            "wezterm" => vec!["kitty".to_string(), "missing".to_string()],
+            "kitty" => vec!["ghostty".to_string()],
+            _ => vec![],
+        };
+        let ordered = order_by_dependencies(&["ghostty", "wezterm", "kitty"], dependencies).unwrap();
+        assert_eq!(ordered, vec!["ghostty", "kitty", "wezterm"]);
+
+        let cycle = |id: &str| vec![if id == "a" { "b" } else { "a" }.to_string()];
+        assert!(order_by_dependencies(&["a", "b"], cycle).is_err());
+    }
+
+    #[test]
+    fn rollback_restores_files_and_removes_created_dirs() {
+        let root = std::env::temp_dir().join(format!("ghostty-ultimate-journal-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        let existing = root.join("existing.conf");
+        let removed = root.join("removed.conf");
+        fs::write(&existing, "before").unwrap();
+        fs::write(&removed, "keep me").unwrap();
+
+        let mut journal = Journal::default();
+        journal.write(&existing, "first").unwrap();
+        journal.write(&existing, "second").unwrap();
+        journal.write(&root.join("new/dir/file.toml"), "x").unwrap();
+        journal.remove(&removed).unwrap();
+        assert_eq!(journal.paths().len(), 3);
+
+        let restored = journal.rollback();
+        assert_eq!(restored.len(), 3);
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "before");
+        assert_eq!(fs::read_to_string(&removed).unwrap(), "keep me");
+        assert!(!root.join("new").exists());
+        assert!(journal.paths().is_empty());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn a_failed_step_rolls_back_the_steps_before_it() {
+        let root = std::env::temp_dir().join(format!("ghostty-ultimate-apply-all-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let target = ApplyTarget { root: Some(root.to_string_lossy().to_string()) };
+        let sections: Vec<String> = ["zshrc", "git", "neovim", "starship"].iter().map(|s| s.to_string()).collect();
+        let mut config = GhosttyConfig::default();
+        config.neovim.enabled = false;
+        // No signing key exists under the root, so the git step fails
+        config.git.signing.enabled = true;
+
+        let report = apply_all(&config, &sections, &target).unwrap();
+        assert!(!report.success);
+        let steps: Vec<(&str, &StepStatus)> = report.steps.iter().map(|s| (s.section.as_str(), &s.status)).collect();
+        assert_eq!(
+            steps,
+            [
+                ("starship", &StepStatus::RolledBack),
+                ("neovim", &StepStatus::Skipped),
+                ("git", &StepStatus::Failed),
+                ("zshrc", &StepStatus::Skipped),
+            ]
+        );
+        assert!(report.steps[2].message.contains("Signing key not found"), "{}", report.steps[2].message);
+        assert!(report.rolled_back.iter().any(|p| p.ends_with(".config/starship.toml")));
+        assert!(!root.join(".config/starship.toml").exists());
+
+        config.git.signing.enabled = false;
+        let report = apply_all(&config, &sections, &target).unwrap();
+        assert!(report.success);
+        assert!(report.steps[0].files.iter().any(|p| p.ends_with(".config/starship.toml")));
+        assert!(report.steps.iter().all(|s| s.hook_output.is_empty()));
+        assert!(root.join(".zshrc").is_file());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
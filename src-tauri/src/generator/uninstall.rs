@@ -0,0 +1,562 @@
+use crate::config::schema::GhosttyConfig;
+use crate::config::sections::{GhosttyLayout, GitApplyMode, GitSigningConfig, SigningFormat};
+use super::applier::{self, ApplyTarget, GHOSTTY_INCLUDES, MANAGED_GITCONFIG, ZSHRC_END_MARKER, ZSHRC_START_MARKER};
+use super::custom;
+use super::registry;
+use super::transaction::Journal;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Legacy Ghostty config the setup moves aside, relative to the home directory.
+const GHOSTTY_MACOS_CONFIG: &str = "Library/Application Support/com.mitchellh.ghostty/config";
+
+/// Section id for what the script itself creates, outside any section.
+pub const SETUP_SECTION: &str = "setup";
+
+/// `--resume` state written by every run of the setup script.
+const SCRIPT_STATE_FILE: &str = ".local/state/ghostty-ultimate/completed-sections";
+
+const TMUX_SCRIPTS: [&str; 5] = ["tmux-ai", "tmux-pair", "tmux-review", "tmux-dev", "tmux-cheat"];
+
+/// Something the setup creates. Paths are relative to the home directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Artifact {
+    /// A file written in full.
+    File {
+        path: String,
+        /// Restore from backups of this path instead of the file itself,
+        /// e.g. the whole nvim directory the setup moved aside.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        backup_of: Option<String>,
+    },
+    /// A git clone, removed with its contents.
+    Checkout { path: String },
+    /// The marked block the setup adds to a file the user also edits.
+    Block { path: String, start: String, end: String },
+    /// An `include.path` entry in the user's global gitconfig.
+    GitInclude { include: String },
+    /// A file the setup moved aside. Only brought back when restoring backups.
+    Replaced { path: String },
+    /// A Homebrew formula or cask.
+    Package { name: String, cask: bool },
+}
+
+/// One artifact and the section that creates it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallEntry {
+    pub section: String,
+    pub artifact: Artifact,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UninstallOptions {
+    /// Put back the newest `.bak.<time>` copy of each file instead of only deleting it.
+    #[serde(default)]
+    pub restore_backups: bool,
+    /// `brew uninstall` the packages and font casks.
+    #[serde(default)]
+    pub remove_packages: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UninstallReport {
+    /// Files, checkouts and blocks removed, relative to the target home.
+    pub removed: Vec<String>,
+    /// Paths put back from a backup.
+    pub restored: Vec<String>,
+    /// Packages uninstalled.
+    pub packages: Vec<String>,
+    /// Anything left in place, with the reason.
+    pub skipped: Vec<String>,
+    /// Checkouts and packages that failed to go once the file changes
+    /// were already made.
+    pub errors: Vec<String>,
+}
+
+// ── Install manifest ────────────────────────────────────────────────
+
+fn file(path: impl Into<String>) -> Artifact {
+    Artifact::File { path: path.into(), backup_of: None }
+}
+
+fn checkout(path: impl Into<String>) -> Artifact {
+    Artifact::Checkout { path: path.into() }
+}
+
+/// What one enabled section creates.
+fn section_artifacts(config: &GhosttyConfig, section: &str) -> Vec<Artifact> {
+    match section {
+        "packages" => {
+            let packages = &config.packages;
+            let mut artifacts: Vec<Artifact> = packages
+                .packages
+                .iter()
+                .filter(|p| p.enabled)
+                .map(|p| Artifact::Package { name: p.name.clone(), cask: false })
+                .collect();
+            if packages.install_jetbrains_font {
+                artifacts.push(Artifact::Package { name: "font-jetbrains-mono".into(), cask: true });
+                if packages.install_nerd_font {
+                    artifacts.push(Artifact::Package { name: "font-jetbrains-mono-nerd-font".into(), cask: true });
+                }
+            }
+            artifacts
+        }
+        "ghostty" => {
+            let ghostty = &config.ghostty;
+            let mut artifacts = vec![
+                Artifact::Replaced { path: GHOSTTY_MACOS_CONFIG.into() },
+                file(format!(".config/ghostty/themes/{}", config.theme_name())),
+                file(".config/ghostty/config"),
+            ];
+            // The `local` file belongs to the user once it exists
+            if ghostty.layout == GhosttyLayout::Modular {
+                artifacts.extend(GHOSTTY_INCLUDES.iter().map(|(name, _)| file(format!(".config/ghostty/{}", name))));
+            }
+            if ghostty.custom_shader_enabled {
+                artifacts.push(file(".config/ghostty/shaders/vignette-bloom.glsl"));
+            }
+            if ghostty.install_community_shaders {
+                artifacts.push(checkout(".config/ghostty/shaders/ghostty-shaders"));
+            }
+            artifacts
+        }
+        "starship" => vec![file(".config/starship.toml")],
+        "fastfetch" => vec![file(".config/fastfetch/config.jsonc"), file(".config/fastfetch/quotes.txt")],
+        "zsh_plugins" => {
+            let mut artifacts: Vec<Artifact> = config
+                .zsh_plugins
+                .plugins
+                .iter()
+                .filter(|p| p.enabled)
+                .map(|p| checkout(format!(".local/share/{}", p.name)))
+                .collect();
+            artifacts.push(file(".config/yazi/yazi.toml"));
+            artifacts.push(file(".config/yazi/keymap.toml"));
+            artifacts
+        }
+        "tmux" => {
+            // TPM clones each plugin into a directory named after its repo
+            let mut artifacts: Vec<Artifact> = config
+                .tmux
+                .plugins
+                .iter()
+                .filter(|p| p.enabled && p.name != "tpm")
+                .filter_map(|p| p.repo.trim_end_matches('/').rsplit('/').next().map(String::from))
+                .map(|name| checkout(format!(".tmux/plugins/{}", name.trim_end_matches(".git"))))
+                .collect();
+            artifacts.push(checkout(".tmux/plugins/tpm"));
+            artifacts.push(file(".tmux.conf"));
+            artifacts
+        }
+        "neovim" => vec![Artifact::File { path: ".config/nvim/init.lua".into(), backup_of: Some(".config/nvim".into()) }],
+        "tool_configs" => vec![
+            file(".config/atuin/config.toml"),
+            file(".config/lazygit/config.yml"),
+            file("Library/Application Support/lazygit/config.yml"),
+        ],
+        "git" => {
+            let git = &config.git;
+            if git.apply_mode == GitApplyMode::Remove {
+                return Vec::new();
+            }
+            let mut artifacts = vec![
+                Artifact::GitInclude { include: format!("~/{}", MANAGED_GITCONFIG) },
+                file(MANAGED_GITCONFIG),
+            ];
+            artifacts.extend(git.identities.iter().filter(|i| i.enabled && i.has_valid_id()).map(|i| file(i.include_file())));
+            let signing = &git.signing;
+            if signing.enabled && signing.format == SigningFormat::Ssh && signing.allowed_signers {
+                artifacts.push(file(GitSigningConfig::ALLOWED_SIGNERS_FILE));
+            }
+            artifacts
+        }
+        "themes" => {
+            let themes = &config.themes;
+            let mut artifacts = Vec::new();
+            if themes.bat_theme_enabled {
+                artifacts.push(file(".config/bat/themes/Ayu Dark.tmTheme"));
+            }
+            artifacts.push(file(".config/btop/btop.conf"));
+            if themes.yazi_theme_enabled {
+                artifacts.push(file(".config/yazi/theme.toml"));
+            }
+            artifacts
+        }
+        "tmux_scripts" => TMUX_SCRIPTS.iter().map(|name| file(format!(".local/bin/{}", name))).collect(),
+        "zshrc" => vec![Artifact::Block {
+            path: ".zshrc".into(),
+            start: ZSHRC_START_MARKER.into(),
+            end: ZSHRC_END_MARKER.into(),
+        }],
+        id => custom::registry()
+            .get(id)
+            .map(|manifest| manifest.files.iter().map(|f| file(f.target.clone())).collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// Everything the setup creates for this config: each enabled section's
+/// artifacts in script order, then the script's own state file.
+pub fn install_manifest(config: &GhosttyConfig) -> Vec<InstallEntry> {
+    let mut entries: Vec<InstallEntry> = registry::enabled_sections(config)
+        .iter()
+        .flat_map(|section| {
+            section_artifacts(config, &section.id)
+                .into_iter()
+                .map(|artifact| InstallEntry { section: section.id.clone(), artifact })
+        })
+        .collect();
+    entries.push(InstallEntry { section: SETUP_SECTION.into(), artifact: file(SCRIPT_STATE_FILE) });
+    entries
+}
+
+/// The install manifest grouped by section, in reverse script order, for
+/// the uninstall script.
+pub fn script_sections(config: &GhosttyConfig) -> Vec<serde_json::Value> {
+    let entries = install_manifest(config);
+    let labels = registry::list_sections();
+    let mut ids: Vec<&str> = Vec::new();
+    for entry in &entries {
+        if !ids.contains(&entry.section.as_str()) {
+            ids.push(&entry.section);
+        }
+    }
+    ids.iter()
+        .rev()
+        .map(|id| {
+            let label = labels.iter().find(|s| s.id == *id).map_or("Setup state", |s| s.label.as_str());
+            let artifacts: Vec<&Artifact> = entries.iter().filter(|e| e.section == *id).map(|e| &e.artifact).collect();
+            serde_json::json!({ "id": id, "label": label, "artifacts": artifacts })
+        })
+        .collect()
+}
+
+// ── Backups ─────────────────────────────────────────────────────────
+
+/// The newest `<path>.bak.<unix time>` the setup script left behind.
+pub fn newest_backup(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_string_lossy().to_string();
+    let prefix = format!("{}.bak.", name);
+    fs::read_dir(path.parent()?)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let stamp: u64 = file_name.strip_prefix(&prefix)?.parse().ok()?;
+            Some((stamp, entry.path()))
+        })
+        .max_by_key(|(stamp, _)| *stamp)
+        .map(|(_, path)| path)
+}
+
+/// Files under `dir`, relative to it.
+fn files_under(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&current) else { continue };
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.is_dir() {
+                pending.push(path);
+            } else if let Ok(relative) = path.strip_prefix(dir) {
+                files.push(relative.to_path_buf());
+            }
+        }
+    }
+    files
+}
+
+/// Put `backup` back at `path`. A directory backup replaces every file
+/// under `path`.
+fn restore_backup(backup: &Path, path: &Path, journal: &mut Journal) -> Result<(), String> {
+    if !backup.is_dir() {
+        let content = fs::read(backup).map_err(|e| format!("Failed to read {}: {}", backup.display(), e))?;
+        return journal.write_bytes(path, &content);
+    }
+    let kept = files_under(backup);
+    for relative in files_under(path).into_iter().filter(|f| !kept.contains(f)) {
+        journal.remove(&path.join(relative))?;
+    }
+    for relative in kept {
+        let source = backup.join(&relative);
+        let content = fs::read(&source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+        journal.write_bytes(&path.join(relative), &content)?;
+    }
+    Ok(())
+}
+
+/// `content` without the lines from `start` to `end`, or `None` when it
+/// has no such block.
+fn strip_block(content: &str, start: &str, end: &str) -> Option<String> {
+    let start_idx = content.find(start)?;
+    let end_idx = start_idx + content[start_idx..].find(end)? + end.len();
+    let before = content[..start_idx].trim_end();
+    let after = content[end_idx..].trim_start_matches('\n');
+    let mut stripped = match (before.is_empty(), after.is_empty()) {
+        (true, _) => after.to_string(),
+        (false, true) => before.to_string(),
+        (false, false) => format!("{}\n\n{}", before, after),
+    };
+    if !stripped.is_empty() && !stripped.ends_with('\n') {
+        stripped.push('\n');
+    }
+    Some(stripped)
+}
+
+// ── Uninstall ───────────────────────────────────────────────────────
+
+/// Undo what the setup created for `sections`. File changes run as one
+/// transaction and are rolled back if any fails; checkouts and packages,
+/// which cannot be rolled back, are only removed once the files are done.
+/// Packages are only uninstalled for the real home.
+pub fn uninstall(
+    config: &GhosttyConfig,
+    sections: &[String],
+    options: &UninstallOptions,
+    target: &ApplyTarget,
+) -> Result<UninstallReport, String> {
+    for section in sections {
+        if !registry::is_known(section) {
+            return Err(format!("Unknown section '{}'", section));
+        }
+    }
+    let home = target.home_dir()?;
+    // The script's state only goes once every enabled section does
+    let everything = registry::enabled_sections(config).iter().all(|s| sections.contains(&s.id));
+    let artifacts: Vec<Artifact> = install_manifest(config)
+        .into_iter()
+        .filter(|e| sections.contains(&e.section) || (everything && e.section == SETUP_SECTION))
+        .map(|e| e.artifact)
+        .collect();
+
+    let mut report = UninstallReport::default();
+    let mut journal = Journal::default();
+    let mut deferred = Vec::new();
+    for artifact in artifacts {
+        match uninstall_file(&artifact, &home, options, target, &mut journal, &mut report) {
+            Ok(true) => {}
+            Ok(false) => deferred.push(artifact),
+            Err(e) => {
+                journal.rollback();
+                return Err(e);
+            }
+        }
+    }
+
+    let mut kept = 0;
+    for artifact in deferred {
+        match artifact {
+            Artifact::Checkout { path } => {
+                let dir = home.join(&path);
+                if !dir.exists() {
+                    report.skipped.push(format!("{} (not present)", path));
+                    continue;
+                }
+                match fs::remove_dir_all(&dir) {
+                    Ok(()) => report.removed.push(path),
+                    Err(e) => report.errors.push(format!("Failed to remove {}: {}", dir.display(), e)),
+                }
+            }
+            Artifact::Package { name, cask } => {
+                if !options.remove_packages {
+                    kept += 1;
+                } else if !target.is_live() {
+                    report.skipped.push(format!("{} (not the real home)", name));
+                } else {
+                    match uninstall_package(&name, cask) {
+                        Ok(true) => report.packages.push(name),
+                        Ok(false) => report.skipped.push(format!("{} (not installed)", name)),
+                        Err(e) => report.errors.push(e),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if kept > 0 {
+        report.skipped.push(format!("{} package(s) kept", kept));
+    }
+    Ok(report)
+}
+
+/// Undo one journaled artifact. Returns `false` for checkouts and packages,
+/// which are handled after every file step succeeded.
+fn uninstall_file(
+    artifact: &Artifact,
+    home: &Path,
+    options: &UninstallOptions,
+    target: &ApplyTarget,
+    journal: &mut Journal,
+    report: &mut UninstallReport,
+) -> Result<bool, String> {
+    let backup_of = |relative: &str| options.restore_backups.then(|| newest_backup(&home.join(relative))).flatten();
+
+    match artifact {
+        Artifact::File { path, backup_of: original } => {
+            let original = original.as_deref().unwrap_or(path);
+            if let Some(backup) = backup_of(original) {
+                restore_backup(&backup, &home.join(original), journal)?;
+                report.restored.push(original.to_string());
+            } else if home.join(path).exists() {
+                journal.remove(&home.join(path))?;
+                report.removed.push(path.clone());
+            } else {
+                report.skipped.push(format!("{} (not present)", path));
+            }
+        }
+        Artifact::Block { path, start, end } => {
+            // The backup is the file from before the setup added the block
+            if let Some(backup) = backup_of(path) {
+                restore_backup(&backup, &home.join(path), journal)?;
+                report.restored.push(path.clone());
+                return Ok(true);
+            }
+            let full = home.join(path);
+            let content = fs::read_to_string(&full).unwrap_or_default();
+            match strip_block(&content, start, end) {
+                Some(stripped) => {
+                    journal.write(&full, &stripped)?;
+                    report.removed.push(format!("{} (managed block)", path));
+                }
+                None => report.skipped.push(format!("{} (no managed block)", path)),
+            }
+        }
+        Artifact::GitInclude { include } => {
+            journal.track(&target.git_config_path()?);
+            applier::unset_git_include(target, include)?;
+            report.removed.push(format!("include.path {}", include));
+        }
+        Artifact::Replaced { path } => match backup_of(path) {
+            Some(backup) => {
+                restore_backup(&backup, &home.join(path), journal)?;
+                report.restored.push(path.clone());
+            }
+            None => report.skipped.push(format!("{} (moved aside by setup; restore backups to bring it back)", path)),
+        },
+        Artifact::Checkout { .. } | Artifact::Package { .. } => return Ok(false),
+    }
+    Ok(true)
+}
+
+/// `brew uninstall` a formula or cask. Returns `false` when it is not installed.
+fn uninstall_package(name: &str, cask: bool) -> Result<bool, String> {
+    let kind = if cask { "--cask" } else { "--formula" };
+    let installed = Command::new("brew")
+        .args(["list", kind, name])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+    if !installed {
+        return Ok(false);
+    }
+    let output = Command::new("brew")
+        .args(["uninstall", kind, name])
+        .output()
+        .map_err(|e| format!("Failed to run brew: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("brew uninstall {} failed: {}", name, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn home(test: &str) -> PathBuf {
+        let home = std::env::temp_dir().join(format!("ghostty-ultimate-uninstall-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        fs::create_dir_all(&home).unwrap();
+        home
+    }
+
+    fn zshrc_block() -> String {
+        format!("{}\nalias ll='eza -l'\n{}\n", ZSHRC_START_MARKER, ZSHRC_END_MARKER)
+    }
+
+    #[test]
+    fn strip_block_keeps_the_users_lines() {
+        let strip = |content: &str| strip_block(content, ZSHRC_START_MARKER, ZSHRC_END_MARKER);
+        let block = zshrc_block();
+        assert_eq!(strip(&format!("export A=1\n\n{}\nalias b=c\n", block)).unwrap(), "export A=1\n\nalias b=c\n");
+        assert_eq!(strip(&format!("export A=1\n{}", block)).unwrap(), "export A=1\n");
+        assert_eq!(strip(&block).unwrap(), "");
+        assert_eq!(strip("export A=1\n"), None);
+        assert_eq!(strip(&format!("{}\nalias ll='eza -l'\n", ZSHRC_START_MARKER)), None);
+    }
+
+    #[test]
+    fn newest_backup_picks_the_latest_stamp_of_that_file() {
+        let home = home("newest");
+        for name in ["init.lua.bak.20", "init.lua.bak.100", "init.lua.bak.old", "init.luac.bak.999"] {
+            fs::write(home.join(name), name).unwrap();
+        }
+        assert_eq!(newest_backup(&home.join("init.lua")), Some(home.join("init.lua.bak.100")));
+        assert_eq!(newest_backup(&home.join("starship.toml")), None);
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn restoring_a_directory_backup_replaces_every_file() {
+        let home = home("restore");
+        let (nvim, backup) = (home.join("nvim"), home.join("nvim.bak.5"));
+        fs::create_dir_all(nvim.join("lua")).unwrap();
+        fs::create_dir_all(backup.join("lua")).unwrap();
+        fs::write(nvim.join("init.lua"), "managed").unwrap();
+        fs::write(nvim.join("lua/extra.lua"), "managed").unwrap();
+        fs::write(backup.join("init.lua"), "mine").unwrap();
+        fs::write(backup.join("lua/plugins.lua"), "mine").unwrap();
+
+        let mut journal = Journal::default();
+        restore_backup(&backup, &nvim, &mut journal).unwrap();
+        assert_eq!(fs::read_to_string(nvim.join("init.lua")).unwrap(), "mine");
+        assert_eq!(fs::read_to_string(nvim.join("lua/plugins.lua")).unwrap(), "mine");
+        assert!(!nvim.join("lua/extra.lua").exists());
+
+        journal.rollback();
+        assert_eq!(fs::read_to_string(nvim.join("init.lua")).unwrap(), "managed");
+        assert!(nvim.join("lua/extra.lua").exists());
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn a_restored_zshrc_is_not_stripped_again() {
+        let home = home("zshrc");
+        fs::write(home.join(".zshrc"), format!("export A=1\n{}", zshrc_block())).unwrap();
+        fs::write(home.join(".zshrc.bak.7"), "export A=0\n").unwrap();
+        let target = ApplyTarget { root: Some(home.to_string_lossy().to_string()) };
+        let sections = vec!["zshrc".to_string()];
+
+        let restore = UninstallOptions { restore_backups: true, ..Default::default() };
+        let report = uninstall(&GhosttyConfig::default(), &sections, &restore, &target).unwrap();
+        assert_eq!(report.restored, [".zshrc"]);
+        assert!(report.removed.is_empty() && report.skipped.is_empty(), "{:?}", report);
+        assert_eq!(fs::read_to_string(home.join(".zshrc")).unwrap(), "export A=0\n");
+
+        fs::write(home.join(".zshrc"), format!("export A=1\n{}", zshrc_block())).unwrap();
+        let report = uninstall(&GhosttyConfig::default(), &sections, &UninstallOptions::default(), &target).unwrap();
+        assert_eq!(report.removed, [".zshrc (managed block)"]);
+        assert_eq!(fs::read_to_string(home.join(".zshrc")).unwrap(), "export A=1\n");
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn failures_after_the_file_changes_are_reported() {
+        let home = home("checkout");
+        fs::create_dir_all(home.join(".tmux/plugins")).unwrap();
+        fs::write(home.join(".tmux/plugins/tpm"), "not a checkout").unwrap();
+        fs::write(home.join(".tmux.conf"), "set -g mouse on\n").unwrap();
+        let target = ApplyTarget { root: Some(home.to_string_lossy().to_string()) };
+
+        let report =
+            uninstall(&GhosttyConfig::default(), &["tmux".to_string()], &UninstallOptions::default(), &target).unwrap();
+        assert!(report.removed.contains(&".tmux.conf".to_string()), "{:?}", report);
+        assert_eq!(report.errors.len(), 1, "{:?}", report);
+        assert!(report.errors[0].contains(".tmux/plugins/tpm"));
+        let _ = fs::remove_dir_all(&home);
+    }
+}
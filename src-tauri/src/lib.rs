@@ -3,9 +3,25 @@ mod executor;
 mod generator;
 mod profiles;
 
+use config::catalog::{OptionCatalog, OptionDoc};
+use config::keybind::KeybindDiagnostic;
+use config::keymap::KeyConflict;
+use config::requirements::{RequirementEdge, RequirementFix};
 use config::schema::GhosttyConfig;
 use config::palette::NamedPalette;
 use executor::parser::{ExecutionEvent, parse_line};
+use executor::reload::ReloadResult;
+use executor::sandbox::{SandboxOptions, SandboxReport};
+use generator::applier::ApplyTarget;
+use generator::custom::CustomSections;
+use generator::export::{ExportFormat, ExportReport};
+use generator::overrides::TemplateOverride;
+use generator::registry::SectionInfo;
+use generator::selection::SectionSelection;
+use generator::shaders::{ShaderDiagnostic, ShaderInfo};
+use generator::signing::SigningCheck;
+use generator::transaction::ApplyReport;
+use generator::uninstall::{InstallEntry, UninstallOptions, UninstallReport};
 use std::sync::atomic::{AtomicBool, Ordering};
 use tauri::ipc::Channel;
 
@@ -22,8 +38,57 @@ fn validate_config(config: GhosttyConfig) -> Result<Vec<String>, String> {
 }
 
 #[tauri::command]
-fn generate_script(config: GhosttyConfig) -> Result<String, String> {
-    generator::engine::generate(&config)
+fn check_requirements(config: GhosttyConfig) -> Vec<RequirementEdge> {
+    config::requirements::graph(&config)
+}
+
+#[tauri::command]
+fn fix_requirements(mut config: GhosttyConfig) -> RequirementFix {
+    let changes = config::requirements::auto_fix(&mut config);
+    RequirementFix { config, changes }
+}
+
+#[tauri::command]
+async fn ghostty_option_catalog() -> OptionCatalog {
+    config::catalog::catalog().clone()
+}
+
+#[tauri::command]
+async fn search_ghostty_options(query: String) -> Vec<OptionDoc> {
+    config::catalog::catalog().search(&query)
+}
+
+/// Check the rendered Ghostty config against the option catalog.
+#[tauri::command]
+async fn validate_ghostty_options(config: GhosttyConfig) -> Result<Vec<String>, String> {
+    let rendered = generator::engine::render_apply_template(&config, "apply/ghostty_config.tera")?;
+    Ok(config::catalog::catalog().validate_config_text(&rendered))
+}
+
+#[tauri::command]
+fn check_keybinds(config: GhosttyConfig) -> Vec<KeybindDiagnostic> {
+    config::keybind::check(&config.ghostty.keybinds)
+}
+
+/// Keys that collide across Ghostty, tmux and the shell.
+#[tauri::command]
+fn analyze_keybindings(config: GhosttyConfig) -> Vec<KeyConflict> {
+    config::keymap::analyze(&config)
+}
+
+#[tauri::command]
+fn generate_script(config: GhosttyConfig, selection: Option<SectionSelection>) -> Result<String, String> {
+    generator::engine::generate(&config, &selection.unwrap_or_default())
+}
+
+#[tauri::command]
+fn generate_uninstall_script(config: GhosttyConfig, options: Option<UninstallOptions>) -> Result<String, String> {
+    generator::engine::generate_uninstall(&config, &options.unwrap_or_default())
+}
+
+#[tauri::command]
+fn generate_home_manager(config: GhosttyConfig) -> Result<String, String> {
+    generator::home_manager::generate(&config)
 }
 
 #[tauri::command]
@@ -61,6 +126,11 @@ fn export_profile(config: GhosttyConfig, path: String) -> Result<(), String> {
     profiles::manager::export(&config, &path)
 }
 
+#[tauri::command]
+fn export_dotfiles(config: GhosttyConfig, path: String, format: Option<ExportFormat>) -> Result<ExportReport, String> {
+    generator::export::export(&config, std::path::Path::new(&path), format.unwrap_or_default())
+}
+
 #[tauri::command]
 fn import_profile(path: String) -> Result<GhosttyConfig, String> {
     profiles::manager::import(&path)
@@ -77,14 +147,22 @@ fn detect_installed_fonts() -> Result<Vec<String>, String> {
 }
 
 #[tauri::command]
-async fn execute_script(script_path: String, on_event: Channel<ExecutionEvent>) -> Result<(), String> {
+async fn execute_script(
+    script_path: String,
+    selection: Option<SectionSelection>,
+    on_event: Channel<ExecutionEvent>,
+) -> Result<(), String> {
     use std::io::{BufRead, BufReader};
     use std::process::{Command, Stdio};
 
     CANCEL_FLAG.store(false, Ordering::SeqCst);
 
+    let selection = selection.unwrap_or_default();
+    selection.validate()?;
+
     let mut child = Command::new("bash")
         .arg(&script_path)
+        .args(selection.to_args())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()
@@ -140,8 +218,8 @@ async fn execute_script(script_path: String, on_event: Channel<ExecutionEvent>)
     if status.success() {
         let _ = on_event.send(ExecutionEvent {
             event_type: "complete".into(),
-            section: Some(13),
-            total_sections: Some(13),
+            section: None,
+            total_sections: None,
             message: "Script completed successfully".into(),
             timestamp: ts,
         });
@@ -165,12 +243,106 @@ fn cancel_execution() -> Result<(), String> {
 }
 
 #[tauri::command]
-fn apply_config(config: GhosttyConfig, section: String) -> Result<String, String> {
-    generator::applier::apply_section(&config, &section)
+async fn sandbox_preview(
+    config: GhosttyConfig,
+    selection: Option<SectionSelection>,
+    options: Option<SandboxOptions>,
+) -> Result<SandboxReport, String> {
+    // The selection is baked into the script's default arguments
+    let script = generator::engine::generate(&config, &selection.unwrap_or_default())?;
+    executor::sandbox::run_script(&script, &[], &options.unwrap_or_default())
+}
+
+#[tauri::command]
+async fn sandbox_apply(
+    config: GhosttyConfig,
+    sections: Vec<String>,
+    options: Option<SandboxOptions>,
+) -> Result<SandboxReport, String> {
+    executor::sandbox::run_applier(&config, &sections, &options.unwrap_or_default())
+}
+
+#[tauri::command]
+fn apply_config(config: GhosttyConfig, section: String, target: Option<ApplyTarget>) -> Result<String, String> {
+    generator::applier::apply_section(&config, &section, &target.unwrap_or_default())
+}
+
+#[tauri::command]
+fn apply_all(
+    config: GhosttyConfig,
+    sections: Vec<String>,
+    target: Option<ApplyTarget>,
+) -> Result<ApplyReport, String> {
+    generator::transaction::apply_all(&config, &sections, &target.unwrap_or_default())
+}
+
+#[tauri::command]
+fn uninstall_manifest(config: GhosttyConfig) -> Vec<InstallEntry> {
+    generator::uninstall::install_manifest(&config)
+}
+
+#[tauri::command]
+fn uninstall(
+    config: GhosttyConfig,
+    sections: Vec<String>,
+    options: Option<UninstallOptions>,
+    target: Option<ApplyTarget>,
+) -> Result<UninstallReport, String> {
+    generator::uninstall::uninstall(&config, &sections, &options.unwrap_or_default(), &target.unwrap_or_default())
+}
+
+#[tauri::command]
+fn reload_tools(hooks: Vec<String>) -> Result<Vec<ReloadResult>, String> {
+    let home = dirs::home_dir().ok_or("Cannot find home directory")?;
+    hooks.iter().map(|hook| executor::reload::run_hook(hook, &home)).collect()
+}
+
+#[tauri::command]
+async fn check_git_signing(config: GhosttyConfig, target: Option<ApplyTarget>) -> Result<SigningCheck, String> {
+    let home = target.unwrap_or_default().home_dir()?;
+    Ok(generator::signing::check_signing(&config, &home))
+}
+
+#[tauri::command]
+async fn list_shaders(target: Option<ApplyTarget>) -> Result<Vec<ShaderInfo>, String> {
+    generator::shaders::list_shaders(&target.unwrap_or_default().home_dir()?)
+}
+
+#[tauri::command]
+async fn import_shader(path: String, target: Option<ApplyTarget>) -> Result<ShaderInfo, String> {
+    let home = target.unwrap_or_default().home_dir()?;
+    generator::shaders::import_shader(&home, std::path::Path::new(&path))
+}
+
+#[tauri::command]
+fn validate_shader(source: String) -> Vec<ShaderDiagnostic> {
+    generator::shaders::validate_glsl(&source)
+}
+
+#[tauri::command]
+fn list_template_overrides(config: GhosttyConfig) -> Result<Vec<TemplateOverride>, String> {
+    generator::overrides::list_overrides(&config)
+}
+
+#[tauri::command]
+fn list_sections() -> Vec<SectionInfo> {
+    generator::registry::list_sections()
+}
+
+#[tauri::command]
+fn list_custom_sections() -> CustomSections {
+    generator::custom::registry().as_ref().clone()
+}
+
+#[tauri::command]
+fn reload_custom_sections() -> CustomSections {
+    generator::custom::reload().as_ref().clone()
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Load custom section manifests before the first command needs them
+    generator::custom::registry();
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
@@ -178,7 +350,16 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             get_default_config,
             validate_config,
+            check_requirements,
+            fix_requirements,
+            ghostty_option_catalog,
+            search_ghostty_options,
+            validate_ghostty_options,
+            check_keybinds,
+            analyze_keybindings,
             generate_script,
+            generate_uninstall_script,
+            generate_home_manager,
             preview_section,
             get_builtin_palettes,
             save_profile,
@@ -186,12 +367,27 @@ pub fn run() {
             list_profiles,
             delete_profile,
             export_profile,
+            export_dotfiles,
             import_profile,
             detect_installed_packages,
             detect_installed_fonts,
             execute_script,
             cancel_execution,
+            sandbox_preview,
+            sandbox_apply,
             apply_config,
+            apply_all,
+            uninstall_manifest,
+            uninstall,
+            reload_tools,
+            check_git_signing,
+            list_shaders,
+            import_shader,
+            validate_shader,
+            list_template_overrides,
+            list_sections,
+            list_custom_sections,
+            reload_custom_sections,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -3,6 +3,29 @@ import type {
   GhosttyConfig,
   NamedPalette,
   ExecutionEvent,
+  SectionSelection,
+  SandboxOptions,
+  SandboxReport,
+  ApplyTarget,
+  ApplyReport,
+  ReloadResult,
+  SigningCheck,
+  OptionCatalog,
+  OptionDoc,
+  KeybindDiagnostic,
+  KeyConflict,
+  ShaderInfo,
+  ShaderDiagnostic,
+  TemplateOverride,
+  CustomSections,
+  SectionInfo,
+  RequirementEdge,
+  RequirementFix,
+  InstallEntry,
+  UninstallOptions,
+  UninstallReport,
+  ExportFormat,
+  ExportReport,
 } from "./types";
 
 export async function getDefaultConfig(): Promise<GhosttyConfig> {
@@ -15,10 +38,61 @@ export async function validateConfig(
   return invoke<string[]>("validate_config", { config });
 }
 
+export async function checkRequirements(
+  config: GhosttyConfig,
+): Promise<RequirementEdge[]> {
+  return invoke<RequirementEdge[]>("check_requirements", { config });
+}
+
+/** Enable the packages, fonts and sections that enabled sections need. */
+export async function fixRequirements(
+  config: GhosttyConfig,
+): Promise<RequirementFix> {
+  return invoke<RequirementFix>("fix_requirements", { config });
+}
+
+export async function ghosttyOptionCatalog(): Promise<OptionCatalog> {
+  return invoke<OptionCatalog>("ghostty_option_catalog");
+}
+
+export async function searchGhosttyOptions(query: string): Promise<OptionDoc[]> {
+  return invoke<OptionDoc[]>("search_ghostty_options", { query });
+}
+
+export async function validateGhosttyOptions(
+  config: GhosttyConfig,
+): Promise<string[]> {
+  return invoke<string[]>("validate_ghostty_options", { config });
+}
+
+export async function checkKeybinds(
+  config: GhosttyConfig,
+): Promise<KeybindDiagnostic[]> {
+  return invoke<KeybindDiagnostic[]>("check_keybinds", { config });
+}
+
+export async function analyzeKeybindings(
+  config: GhosttyConfig,
+): Promise<KeyConflict[]> {
+  return invoke<KeyConflict[]>("analyze_keybindings", { config });
+}
+
 export async function generateScript(
   config: GhosttyConfig,
+  selection?: SectionSelection,
+): Promise<string> {
+  return invoke<string>("generate_script", { config, selection });
+}
+
+export async function generateUninstallScript(
+  config: GhosttyConfig,
+  options?: UninstallOptions,
 ): Promise<string> {
-  return invoke<string>("generate_script", { config });
+  return invoke<string>("generate_uninstall_script", { config, options });
+}
+
+export async function generateHomeManager(config: GhosttyConfig): Promise<string> {
+  return invoke<string>("generate_home_manager", { config });
 }
 
 export async function previewSection(
@@ -58,6 +132,14 @@ export async function exportProfile(
   return invoke("export_profile", { config, path });
 }
 
+export async function exportDotfiles(
+  config: GhosttyConfig,
+  path: string,
+  format?: ExportFormat,
+): Promise<ExportReport> {
+  return invoke<ExportReport>("export_dotfiles", { config, path, format });
+}
+
 export async function importProfile(path: string): Promise<GhosttyConfig> {
   return invoke<GhosttyConfig>("import_profile", { path });
 }
@@ -73,6 +155,89 @@ export async function detectInstalledFonts(): Promise<string[]> {
 export async function applyConfig(
   config: GhosttyConfig,
   section: string,
+  target?: ApplyTarget,
 ): Promise<string> {
-  return invoke<string>("apply_config", { config, section });
+  return invoke<string>("apply_config", { config, section, target });
+}
+
+export async function applyAll(
+  config: GhosttyConfig,
+  sections: string[],
+  target?: ApplyTarget,
+): Promise<ApplyReport> {
+  return invoke<ApplyReport>("apply_all", { config, sections, target });
+}
+
+export async function uninstallManifest(config: GhosttyConfig): Promise<InstallEntry[]> {
+  return invoke<InstallEntry[]>("uninstall_manifest", { config });
+}
+
+export async function uninstall(
+  config: GhosttyConfig,
+  sections: string[],
+  options?: UninstallOptions,
+  target?: ApplyTarget,
+): Promise<UninstallReport> {
+  return invoke<UninstallReport>("uninstall", { config, sections, options, target });
+}
+
+export async function reloadTools(hooks: string[]): Promise<ReloadResult[]> {
+  return invoke<ReloadResult[]>("reload_tools", { hooks });
+}
+
+export async function checkGitSigning(
+  config: GhosttyConfig,
+  target?: ApplyTarget,
+): Promise<SigningCheck> {
+  return invoke<SigningCheck>("check_git_signing", { config, target });
+}
+
+export async function listShaders(target?: ApplyTarget): Promise<ShaderInfo[]> {
+  return invoke<ShaderInfo[]>("list_shaders", { target });
+}
+
+export async function importShader(
+  path: string,
+  target?: ApplyTarget,
+): Promise<ShaderInfo> {
+  return invoke<ShaderInfo>("import_shader", { path, target });
+}
+
+export async function validateShader(source: string): Promise<ShaderDiagnostic[]> {
+  return invoke<ShaderDiagnostic[]>("validate_shader", { source });
+}
+
+export async function listTemplateOverrides(
+  config: GhosttyConfig,
+): Promise<TemplateOverride[]> {
+  return invoke<TemplateOverride[]>("list_template_overrides", { config });
+}
+
+export async function listSections(): Promise<SectionInfo[]> {
+  return invoke<SectionInfo[]>("list_sections");
+}
+
+export async function listCustomSections(): Promise<CustomSections> {
+  return invoke<CustomSections>("list_custom_sections");
+}
+
+/** Rescan ~/.config/ghostty-ultimate-ui/sections for manifests. */
+export async function reloadCustomSections(): Promise<CustomSections> {
+  return invoke<CustomSections>("reload_custom_sections");
+}
+
+export async function sandboxPreview(
+  config: GhosttyConfig,
+  selection?: SectionSelection,
+  options?: SandboxOptions,
+): Promise<SandboxReport> {
+  return invoke<SandboxReport>("sandbox_preview", { config, selection, options });
+}
+
+export async function sandboxApply(
+  config: GhosttyConfig,
+  sections: string[],
+  options?: SandboxOptions,
+): Promise<SandboxReport> {
+  return invoke<SandboxReport>("sandbox_apply", { config, sections, options });
 }
@@ -28,6 +28,8 @@ export interface NamedPalette {
 
 export interface GhosttyConfig {
   palette: AyuPalette;
+  /** Preset the palette came from; names the Ghostty theme file. */
+  palette_name: string;
   clean_artifacts: CleanArtifactsConfig;
   packages: PackagesConfig;
   ghostty: GhosttyTermConfig;
@@ -41,6 +43,8 @@ export interface GhosttyConfig {
   themes: ThemesConfig;
   tmux_scripts: TmuxScriptsConfig;
   zshrc: ZshrcConfig;
+  /** Sections defined by manifests in ~/.config/ghostty-ultimate-ui/sections. */
+  custom_sections: Record<string, CustomSectionConfig>;
 }
 
 // Section 1
@@ -136,11 +140,126 @@ export interface GhosttyTermConfig {
   custom_shader_enabled: boolean;
   custom_shader_path: string;
   install_community_shaders: boolean;
+  /** Library shaders chained after `custom_shader_path`, in order. */
+  shaders: ShaderEntry[];
   keybinds: KeyBind[];
   quick_terminal_position: string;
   quick_terminal_size: string;
   quick_terminal_animation_duration: number;
   quick_terminal_autohide: boolean;
+  extra_options: GhosttyOption[];
+  layout: GhosttyLayout;
+}
+
+/** `modular` splits the config into `config-file` includes plus a user-owned `local`. */
+export type GhosttyLayout = "single" | "modular";
+
+/** A shader from `~/.config/ghostty/shaders`, by path relative to it. */
+export interface ShaderEntry {
+  name: string;
+  enabled: boolean;
+}
+
+export interface ShaderDiagnostic {
+  line: number | null;
+  column: number | null;
+  message: string;
+}
+
+export interface ShaderInfo {
+  name: string;
+  path: string;
+  size: number;
+  valid: boolean;
+  diagnostics: ShaderDiagnostic[];
+}
+
+/** A user template in ~/.config/ghostty-ultimate-ui/templates. */
+export interface TemplateOverride {
+  name: string;
+  path: string;
+  replaces_builtin: boolean;
+  diff: string;
+  output_diff: string;
+  error: string | null;
+}
+
+/** State of a manifest-defined section. */
+export interface CustomSectionConfig {
+  enabled: boolean;
+  /** Values for the manifest's settings schema; missing keys take the schema defaults. */
+  settings: Record<string, any> | null;
+}
+
+export interface ManifestFile {
+  template: string;
+  /** Destination relative to the home directory. */
+  target: string;
+  syntax: "toml" | "json" | "jsonc" | "yaml" | null;
+}
+
+export type ReloadHook = string | { command: string[] };
+
+/** JSON Schema subset understood by the custom section editor. */
+export interface SettingSchema {
+  type?: string | string[];
+  title?: string;
+  description?: string;
+  default?: unknown;
+  enum?: unknown[];
+  minimum?: number;
+  maximum?: number;
+  minLength?: number;
+  maxLength?: number;
+  pattern?: string;
+  items?: SettingSchema;
+  properties?: Record<string, SettingSchema>;
+  required?: string[];
+  additionalProperties?: boolean;
+}
+
+export interface SectionManifest {
+  id: string;
+  label: string;
+  icon: string;
+  description: string;
+  settings: SettingSchema;
+  files: ManifestFile[];
+  script: string | null;
+  reload: ReloadHook[];
+  requires: Requirement[];
+  /** Directory the manifest was loaded from. */
+  dir: string;
+}
+
+export interface CustomSections {
+  sections: SectionManifest[];
+  /** Manifests that could not be loaded. */
+  errors: string[];
+}
+
+/** A raw `key = value` line appended to the Ghostty config. */
+export interface GhosttyOption {
+  key: string;
+  value: string;
+}
+
+export type OptionKind = "bool" | "number" | "color" | "enum" | "string";
+
+/** One entry of `ghostty +show-config --default --docs`. */
+export interface OptionDoc {
+  name: string;
+  kind: OptionKind;
+  defaults: string[];
+  repeatable: boolean;
+  values: string[];
+  docs: string;
+}
+
+export interface OptionCatalog {
+  /** "installed" when read from the ghostty binary, "bundled" otherwise. */
+  source: string;
+  options: OptionDoc[];
 }
 
 export interface KeyBind {
@@ -148,6 +267,33 @@ export interface KeyBind {
   action: string;
 }
 
+export type KeyLayer = "terminal" | "tmux_root" | "tmux_prefix" | "shell";
+
+/** A binding from any tool, in Ghostty's key-chord notation. */
+export interface BoundKey {
+  tool: string;
+  layer: KeyLayer;
+  keys: { mods: string[]; key: string; physical: boolean }[];
+  action: string;
+  source: string;
+}
+
+export interface KeyConflict {
+  kind: "conflict" | "unreachable";
+  winner: BoundKey;
+  shadowed: BoundKey;
+  message: string;
+}
+
+/** A problem with one keybind; `index` points into `ghostty.keybinds`. */
+export interface KeybindDiagnostic {
+  index: number;
+  key: string;
+  action: string;
+  level: "error" | "warning";
+  message: string;
+}
+
 // Section 4
 export interface StarshipConfig {
   enabled: boolean;
@@ -368,8 +514,11 @@ export interface LazygitConfig {
 }
 
 // Section 10
+export type GitApplyMode = "override" | "only_if_unset" | "remove";
+
 export interface GitConfig {
   enabled: boolean;
+  apply_mode: GitApplyMode;
   delta: DeltaConfig;
   merge_conflictstyle: string;
   diff_algorithm: string;
@@ -382,6 +531,8 @@ export interface GitConfig {
   core_editor: string;
   help_autocorrect: number;
   aliases: GitAlias[];
+  identities: GitIdentity[];
+  signing: GitSigningConfig;
 }
 
 export interface DeltaConfig {
@@ -405,6 +556,22 @@ export interface DeltaConfig {
   wrap_max_lines: number;
   true_color: string;
   hyperlinks: boolean;
+  from_palette: boolean;
+  overrides: DeltaStyleOverrides;
+}
+
+/** Empty string = use the palette-derived style. */
+export interface DeltaStyleOverrides {
+  file_style: string;
+  file_decoration_style: string;
+  hunk_header_style: string;
+  hunk_header_decoration_style: string;
+  minus_style: string;
+  plus_style: string;
+  minus_emph_style: string;
+  plus_emph_style: string;
+  zero_style: string;
+  whitespace_error_style: string;
 }
 
 export interface GitAlias {
@@ -413,6 +580,27 @@ export interface GitAlias {
   enabled: boolean;
 }
 
+export type SigningFormat = "ssh" | "openpgp";
+
+export interface GitSigningConfig {
+  enabled: boolean;
+  format: SigningFormat;
+  key: string;
+  sign_commits: boolean;
+  sign_tags: boolean;
+  allowed_signers: boolean;
+  ssh_program: string;
+}
+
+export interface GitIdentity {
+  id: string;
+  enabled: boolean;
+  user_name: string;
+  email: string;
+  signing_key: string;
+  directories: string[];
+}
+
 // Section 11
 export interface ThemesConfig {
   enabled: boolean;
@@ -496,26 +684,130 @@ export interface ExecutionEvent {
   timestamp: number;
 }
 
-// Section metadata for sidebar navigation
+// Which sections the generated script runs (mirrors --only/--skip/--from/--resume)
+export interface SectionSelection {
+  only: string[];
+  skip: string[];
+  from: string | null;
+  resume: boolean;
+}
+
+// Alternate root for the applier (null = current user's home)
+export interface ApplyTarget {
+  root: string | null;
+}
+
+// Transactional multi-section apply
+export interface StepResult {
+  section: string;
+  status: "written" | "skipped" | "failed" | "rolled_back";
+  message: string;
+  files: string[];
+  hook_output: string[];
+}
+
+export interface ApplyReport {
+  success: boolean;
+  steps: StepResult[];
+  rolled_back: string[];
+}
+
+// Everything the setup creates, used to uninstall it (paths relative to home)
+export type Artifact =
+  | { kind: "file"; path: string; backup_of?: string }
+  | { kind: "checkout"; path: string }
+  | { kind: "block"; path: string; start: string; end: string }
+  | { kind: "git_include"; include: string }
+  | { kind: "replaced"; path: string }
+  | { kind: "package"; name: string; cask: boolean };
+
+export interface InstallEntry {
+  section: string;
+  artifact: Artifact;
+}
+
+export interface UninstallOptions {
+  restore_backups: boolean;
+  remove_packages: boolean;
+}
+
+export interface UninstallReport {
+  removed: string[];
+  restored: string[];
+  packages: string[];
+  skipped: string[];
+  errors: string[];
+}
+
+// Dotfiles export: a home/ tree with manifest.json and install.sh, the same as
+// a tarball, a chezmoi source directory, or one GNU stow package per tool
+export type ExportFormat = "tree" | "tarball" | "chezmoi" | "stow" | "home_manager";
+
+export interface ExportReport {
+  path: string;
+  files: string[];
+}
+
+// Reload hooks for running tools ("ghostty" | "neovim" | "tmux" | "bat" | "yazi")
+export interface ReloadResult {
+  hook: string;
+  instances: string[];
+  reloaded: string[];
+  /** Instances that only pick up the new config after a restart. */
+  restart_needed: string[];
+  errors: string[];
+}
+
+// Sandboxed preview run against a throwaway HOME
+export interface SigningCheck {
+  key_found: boolean;
+  signed: boolean;
+  verified: boolean | null;
+  log: string[];
+}
+
+export interface SandboxOptions {
+  seed_paths: string[];
+  keep: boolean;
+}
+
+export interface SandboxReport {
+  root: string | null;
+  exit_code: number | null;
+  created: string[];
+  modified: string[];
+  deleted: string[];
+  stubbed_calls: string[];
+  log: string[];
+}
+
+/** A built-in or custom section, from the backend's section registry. */
 export interface SectionInfo {
   id: string;
   label: string;
   icon: string;
+  /** 1-based position in the script. */
   number: number;
+  template: string;
+  depends_on: string[];
+  reload_hooks: string[];
+  live_apply: boolean;
+  custom: boolean;
+}
+
+/** Something a section, or one of its aliases, functions or plugins, needs. */
+export type Requirement = { package: string } | { font: string } | { section: string };
+
+export interface RequirementEdge {
+  section: string;
+  /** The alias, function, plugin or setting that needs it; null for the section itself. */
+  item: string | null;
+  requirement: Requirement;
+  satisfied: boolean;
+  message: string;
 }
 
-export const SECTIONS: SectionInfo[] = [
-  { id: "clean_artifacts", label: "Clean Artifacts", icon: "🧹", number: 1 },
-  { id: "packages", label: "Packages", icon: "📦", number: 2 },
-  { id: "ghostty", label: "Ghostty", icon: "👻", number: 3 },
-  { id: "starship", label: "Starship", icon: "🚀", number: 4 },
-  { id: "fastfetch", label: "Fastfetch", icon: "📊", number: 5 },
-  { id: "zsh_plugins", label: "Zsh Plugins", icon: "🧩", number: 6 },
-  { id: "tmux", label: "tmux", icon: "🖥️", number: 7 },
-  { id: "neovim", label: "Neovim", icon: "✏️", number: 8 },
-  { id: "tool_configs", label: "Tool Configs", icon: "⚙️", number: 9 },
-  { id: "git", label: "Git", icon: "🔀", number: 10 },
-  { id: "themes", label: "Themes", icon: "🎨", number: 11 },
-  { id: "tmux_scripts", label: "tmux Scripts", icon: "📜", number: 12 },
-  { id: "zshrc", label: "Zshrc", icon: "🏠", number: 13 },
-];
+export interface RequirementFix {
+  config: GhosttyConfig;
+  changes: string[];
+}
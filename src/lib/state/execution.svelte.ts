@@ -3,7 +3,8 @@ import type { ExecutionEvent } from "$lib/api/types";
 class ExecutionState {
   running = $state(false);
   progress = $state(0);
-  totalSections = $state(13);
+  /** Taken from the script's section headers ("3/11 — ..."). */
+  totalSections = $state(0);
   currentSection = $state("");
   logs = $state<ExecutionEvent[]>([]);
   errors = $state<string[]>([]);
@@ -13,6 +14,7 @@ class ExecutionState {
     this.logs = [...this.logs, event];
     if (event.event_type === "progress" && event.section !== null) {
       this.progress = event.section;
+      this.totalSections = event.total_sections ?? this.totalSections;
       this.currentSection = event.message;
     }
     if (event.event_type === "error") {
@@ -28,6 +30,7 @@ class ExecutionState {
   start() {
     this.running = true;
     this.progress = 0;
+    this.totalSections = 0;
     this.logs = [];
     this.errors = [];
     this.completed = false;